@@ -0,0 +1,148 @@
+use std::collections::{HashMap, VecDeque};
+
+/// A match of pattern number `pattern` at the bytes `start..end` of the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    /// The longest proper suffix of this node's string that is also in the trie.
+    fail: usize,
+    /// Patterns ending at this node, including the ones ending at the nodes on the fail chain.
+    outputs: Vec<usize>,
+}
+
+/// Aho-Corasick automaton that finds all occurrences of a set of patterns in a single pass over the text.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+    pattern_lens: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Builds the automaton for the patterns, which are numbered in the order given. Empty patterns never
+    /// match.
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        let mut nodes = vec![Node::default()];
+        let mut pattern_lens = Vec::new();
+        for (pattern, bytes) in patterns.into_iter().enumerate() {
+            let bytes = bytes.as_ref();
+            pattern_lens.push(bytes.len());
+            if bytes.is_empty() {
+                continue;
+            }
+            let mut node = 0;
+            for &byte in bytes {
+                node = match nodes[node].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+            nodes[node].outputs.push(pattern);
+        }
+
+        // Fail links in breadth-first order, so that the links of the shorter strings are known
+        let mut queue: VecDeque<usize> = nodes[0].children.values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = nodes[node].children.iter().map(|(&byte, &child)| (byte, child)).collect();
+            for (byte, child) in children {
+                let mut fail = nodes[node].fail;
+                let child_fail = loop {
+                    if let Some(&next) = nodes[fail].children.get(&byte) {
+                        break next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = nodes[fail].fail;
+                };
+                nodes[child].fail = child_fail;
+                let inherited = nodes[child_fail].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        AhoCorasick { nodes, pattern_lens }
+    }
+
+    pub fn num_patterns(&self) -> usize {
+        self.pattern_lens.len()
+    }
+
+    /// All matches in `text`, overlapping ones included, in order of where they end. Matches ending at the
+    /// same position come longest first.
+    pub fn find_overlapping<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item = Match> + 'a {
+        let mut node = 0;
+        text.iter().enumerate().flat_map(move |(i, &byte)| {
+            node = self.step(node, byte);
+            self.nodes[node].outputs.iter().map(move |&pattern| Match {
+                pattern,
+                start: i + 1 - self.pattern_lens[pattern],
+                end: i + 1,
+            })
+        })
+    }
+
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].children.get(&byte) {
+                return next;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_overlapping_matches() {
+        let matcher = AhoCorasick::new(["he", "she", "his", "hers"]);
+        let matches: Vec<(usize, usize)> = matcher.find_overlapping(b"ushers").map(|m| (m.pattern, m.start)).collect();
+        assert_eq!(matches, vec![(1, 1), (0, 2), (3, 2)]);
+
+        let matcher = AhoCorasick::new(["eight", "two", "one", ""]);
+        let matches: Vec<(usize, usize, usize)> = matcher
+            .find_overlapping(b"eightwone")
+            .map(|m| (m.pattern, m.start, m.end))
+            .collect();
+        assert_eq!(matches, vec![(0, 0, 5), (1, 4, 7), (2, 6, 9)]);
+        assert_eq!(matcher.num_patterns(), 4);
+    }
+
+    #[test]
+    fn test_matches_naive_search() {
+        let patterns = ["a", "aa", "ab", "bab", "bb", "abba"];
+        let matcher = AhoCorasick::new(patterns);
+        let text = "abbabaabbaabab";
+        let mut expected = Vec::new();
+        for (pattern, p) in patterns.iter().enumerate() {
+            for start in 0..=text.len() - p.len() {
+                if text[start..].starts_with(p) {
+                    expected.push((start + p.len(), std::cmp::Reverse(p.len()), pattern));
+                }
+            }
+        }
+        expected.sort();
+        let expected: Vec<usize> = expected.into_iter().map(|(_, _, pattern)| pattern).collect();
+        let found: Vec<usize> = matcher.find_overlapping(text.as_bytes()).map(|m| m.pattern).collect();
+        assert_eq!(found, expected);
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fmt::Display, str::FromStr};
+
+/// The answer to one part of a puzzle.
+///
+/// Integer answers are normalized on construction: anything that fits in an `i64` is stored as `Int`, so the
+/// same number always compares equal regardless of the integer type it was computed with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i64),
+    BigInt(i128),
+    Text(String),
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Answer::Int(n) => Some(*n as i128),
+            Answer::BigInt(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        match i64::try_from(value) {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::BigInt(value),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::from(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i32, u32, i64, u64, isize, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(value: Vec<String>) -> Self {
+        Answer::Grid(value)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::BigInt(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    /// Parses an answer back from its `Display` form.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(n) = s.parse::<i128>() {
+            Ok(Answer::from(n))
+        } else if s.contains('\n') {
+            Ok(Answer::Grid(s.lines().map(|line| line.trim().to_string()).collect()))
+        } else {
+            Ok(Answer::Text(s.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_int_normalization() {
+        assert_eq!(Answer::from(42u32), Answer::Int(42));
+        assert_eq!(Answer::from(42u64), Answer::from(42i128));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInt(u64::MAX as i128));
+        assert_eq!(Answer::from(-7i32).as_int(), Some(-7));
+        assert_eq!(Answer::from("abc").as_int(), None);
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let answers = vec![
+            Answer::Int(-12),
+            Answer::BigInt(170141183460469231731687303715884105727),
+            Answer::Text("EFGH".to_string()),
+            Answer::Grid(vec!["#..#".to_string(), ".##.".to_string()]),
+        ];
+        for answer in answers {
+            let parsed: Answer = answer.to_string().parse().unwrap();
+            assert_eq!(parsed, answer);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::{fs, io};
+
+use toml::{Table, Value};
+
+use crate::{Answer, Error};
+
+pub const ANSWERS_PATH: &str = "./answers.toml";
+
+/// Outcome of comparing an answer with the stored one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Unknown,
+}
+
+/// Known answers for the real puzzle inputs, keyed by day and part.
+///
+/// Stored as TOML with one table per day:
+///
+/// ```toml
+/// [day_01]
+/// part_1 = 54845
+/// part_2 = 54130
+/// ```
+///
+/// Integer answers that fit in an `i64` are stored as integers, everything else as strings.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(i32, i32), Answer>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let table = parse_table(text)?;
+
+        let mut answers = Answers::default();
+        for (day_key, parts) in table.iter() {
+            let day_num = parse_key(text, day_key, "day_")?;
+            let Value::Table(parts) = parts else {
+                return Err(key_error(text, day_key, format!("expected a table of parts for `{day_key}`")));
+            };
+            for (part, answer) in parse_parts(text, parts)? {
+                answers.insert(day_num, part, answer);
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Reads the answers from `path`. A missing file has no answers.
+    pub fn load(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text).map_err(|e| format!("Could not parse {path}: {e}")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("Could not read {path}: {e}")),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_toml()).map_err(|e| format!("Could not write {path}: {e}"))
+    }
+
+    pub fn get(&self, day_num: i32, part: i32) -> Option<&Answer> {
+        self.answers.get(&(day_num, part))
+    }
+
+    pub fn insert(&mut self, day_num: i32, part: i32, answer: Answer) {
+        self.answers.insert((day_num, part), answer);
+    }
+
+    pub fn verify(&self, day_num: i32, part: i32, answer: &Answer) -> Verdict {
+        match self.get(day_num, part) {
+            None => Verdict::Unknown,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut table = Table::new();
+        for ((day_num, part), answer) in self.answers.iter() {
+            let value = match answer {
+                Answer::Int(n) => Value::Integer(*n),
+                answer => Value::String(answer.to_string()),
+            };
+            let day = table.entry(format!("day_{day_num:02}")).or_insert(Value::Table(Table::new()));
+            if let Value::Table(day) = day {
+                day.insert(format!("part_{part}"), value);
+            }
+        }
+        toml::to_string(&table).unwrap()
+    }
+}
+
+pub(crate) fn parse_table(text: &str) -> Result<Table, Error> {
+    text.parse().map_err(|err: toml::de::Error| {
+        let (line, column) = line_column(text, err.span().map(|span| span.start).unwrap_or(0));
+        Error::parse(line, column, err.message().trim().replace('\n', ", "))
+    })
+}
+
+/// Parses a table of `part_<number> = <answer>` entries.
+pub(crate) fn parse_parts(text: &str, parts: &Table) -> Result<Vec<(i32, Answer)>, Error> {
+    parts
+        .iter()
+        .map(|(part_key, value)| {
+            let part = parse_key(text, part_key, "part_")?;
+            let answer = match value {
+                Value::Integer(n) => Answer::Int(*n),
+                Value::String(s) => s.parse().unwrap(),
+                _ => return Err(key_error(text, part_key, "expected an integer or a string")),
+            };
+            Ok((part, answer))
+        })
+        .collect()
+}
+
+fn parse_key(text: &str, key: &str, prefix: &str) -> Result<i32, Error> {
+    key.strip_prefix(prefix)
+        .and_then(|num| num.parse().ok())
+        .ok_or_else(|| key_error(text, key, format!("expected `{prefix}<number>`, found `{key}`")))
+}
+
+/// Error pointing at the first occurrence of `key` in the file, which is good enough for locating a bad key.
+pub(crate) fn key_error(text: &str, key: &str, description: impl Into<String>) -> Error {
+    let (line, column) = line_column(text, text.find(key).unwrap_or(0));
+    Error::parse(line, column, description)
+}
+
+/// 1-based line and column of the byte `offset` in `text`.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut answers = Answers::default();
+        answers.insert(1, 1, Answer::Int(54845));
+        answers.insert(13, 2, Answer::BigInt(1 << 100));
+        answers.insert(10, 1, Answer::Text("EFGH".to_string()));
+        answers.insert(10, 2, Answer::Grid(vec!["#..#".to_string(), ".##.".to_string()]));
+        let text = answers.to_toml();
+        assert!(text.contains("[day_01]\npart_1 = 54845\n"));
+        assert_eq!(Answers::parse(&text), Ok(answers));
+    }
+
+    #[test]
+    fn test_verify_and_errors() {
+        let answers = Answers::parse("[day_05]\npart_1 = 35\n").unwrap();
+        assert_eq!(answers.verify(5, 1, &Answer::Int(35)), Verdict::Pass);
+        assert_eq!(answers.verify(5, 1, &Answer::Int(36)), Verdict::Fail { expected: Answer::Int(35) });
+        assert_eq!(answers.verify(5, 2, &Answer::Int(46)), Verdict::Unknown);
+
+        assert_eq!(Answers::parse("[day_05]\npart_one = 35\n"), Err(Error::parse(2, 1, "expected `part_<number>`, found `part_one`")));
+        assert!(matches!(Answers::parse("[day_05]\npart_1 = \n"), Err(Error::Parse { line: 2, .. })));
+    }
+}
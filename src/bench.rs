@@ -0,0 +1,145 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::{DayInfo, Error};
+
+/// Summary statistics of a set of timing samples, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    pub runs: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub mean_ms: f64,
+    pub stddev_ms: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut millis: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(f64::total_cmp);
+
+        let runs = millis.len();
+        let median_ms = if runs.is_multiple_of(2) {
+            (millis[runs / 2 - 1] + millis[runs / 2]) / 2.0
+        } else {
+            millis[runs / 2]
+        };
+        let mean_ms = millis.iter().sum::<f64>() / runs as f64;
+        // Sample standard deviation, a single run has no spread
+        let stddev_ms = if runs > 1 {
+            (millis.iter().map(|m| (m - mean_ms).powi(2)).sum::<f64>() / (runs - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        Some(Stats {
+            runs,
+            min_ms: millis[0],
+            median_ms,
+            mean_ms,
+            stddev_ms,
+        })
+    }
+}
+
+/// Timings of one part of a day.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartBench {
+    pub part: i32,
+    pub answer: String,
+    pub time: Stats,
+    /// Median part time minus median parse time, for days that can parse separately
+    pub solve_median_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayBench {
+    pub day: i32,
+    pub parse: Option<Stats>,
+    pub parts: Vec<PartBench>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub runs: usize,
+    pub warmup: usize,
+    pub days: Vec<DayBench>,
+}
+
+/// Times parsing, if the day parses separately, and each of `parts` on `input`. Fails without timing anything
+/// if the day can't solve the input.
+pub fn bench_day(day: &DayInfo, parts: &[i32], warmup: usize, runs: usize, input: &str) -> Result<DayBench, Error> {
+    let parse = match day.problem.parse(input) {
+        Some(result) => {
+            result.map_err(|err| err.with_day(day.number))?;
+            Stats::from_samples(&time_runs(warmup, runs, || day.problem.parse(input)))
+        }
+        None => None,
+    };
+
+    let mut part_benches = Vec::new();
+    for &part in parts.iter() {
+        let answer = day.solve(part, input)?;
+        let time = Stats::from_samples(&time_runs(warmup, runs, || day.solve(part, input))).unwrap();
+        part_benches.push(PartBench {
+            part,
+            answer: answer.to_string(),
+            time,
+            solve_median_ms: parse.map(|parse| (time.median_ms - parse.median_ms).max(0.0)),
+        });
+    }
+
+    Ok(DayBench {
+        day: day.number,
+        parse,
+        parts: part_benches,
+    })
+}
+
+/// Calls `f` `warmup` times without timing it, and then times `runs` calls of it.
+pub fn time_runs<T>(warmup: usize, runs: usize, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    for _ in 0..warmup {
+        black_box(f());
+    }
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2].into_iter().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.median_ms, 2.5);
+        assert_eq!(stats.mean_ms, 2.5);
+        assert!((stats.stddev_ms - (5.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+        let stats = Stats::from_samples(&[Duration::from_millis(7)]).unwrap();
+        assert_eq!((stats.median_ms, stats.stddev_ms), (7.0, 0.0));
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_time_runs() {
+        let mut calls = 0;
+        let samples = time_runs(2, 3, || calls += 1);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(calls, 5);
+    }
+}
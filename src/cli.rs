@@ -0,0 +1,281 @@
+use std::path::PathBuf;
+
+use aoc2023::days::DAYS;
+
+/// Last day of the calendar, days up to this can be given even if they are not implemented yet.
+pub const LAST_DAY: i32 = 25;
+pub const DEFAULT_RUNS: usize = 5;
+pub const DEFAULT_WARMUP: usize = 1;
+
+pub const USAGE: &str = "\
+Usage: aoc2023 <command> [options]
+
+Commands:
+  run <days>      Run the solutions for the given days
+  bench [days]    Time the given days over several runs (default: all)
+  check [days]    Check the answers of the given days against answers.toml (default: all)
+  new <day>       Create and register a new day from src/days/day_template.rs
+  list            List the days and their inputs
+  help            Print this message
+
+Days are given as a single number (5), a range (1..=10 or 1..11) or `all` for all implemented days.
+`aoc2023 <days> [part]` is a shorthand for `aoc2023 run <days> --part <part>`.
+
+Options:
+  -p, --part <1|2>     Only run the given part
+  -i, --input <path>   Read the input from <path> instead of ./inputs/day_XX.txt
+  -q, --quiet          Only print the answers (or, for check, only the failures)
+  -r, --record         Write the answers of `run` or `check` into answers.toml
+  -h, --help           Print this message
+
+Bench options:
+  -n, --runs <n>       Number of timed runs of each part (default: 5)
+  -w, --warmup <n>     Number of untimed runs before timing (default: 1)
+      --json           Print the results as JSON
+
+New options:
+  -t, --title <title>  Title of the puzzle (default: Day <day>)";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Command {
+    Run,
+    Bench,
+    Check,
+    New,
+    List,
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    pub command: Command,
+    pub day_nums: Vec<i32>,
+    pub parts: Vec<i32>,
+    pub input: Option<PathBuf>,
+    pub quiet: bool,
+    pub record: bool,
+    pub runs: usize,
+    pub warmup: usize,
+    pub json: bool,
+    pub title: Option<String>,
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut args = args.into_iter().peekable();
+
+    let (command, legacy) = match args.peek().map(|s| s.as_str()) {
+        None => return Err("No command given".to_string()),
+        Some("run") => (Command::Run, false),
+        Some("bench") => (Command::Bench, false),
+        Some("check") => (Command::Check, false),
+        Some("new") => (Command::New, false),
+        Some("list") => (Command::List, false),
+        Some("help" | "-h" | "--help") => (Command::Help, false),
+        Some(s) if s.starts_with(|c: char| c.is_ascii_digit()) || s == "all" => (Command::Run, true),
+        Some(s) => return Err(format!("Unknown command `{s}`")),
+    };
+    if !legacy {
+        args.next();
+    }
+
+    let mut positionals = Vec::new();
+    let mut parts = None;
+    let mut input = None;
+    let mut quiet = false;
+    let mut record = false;
+    let mut runs = None;
+    let mut warmup = None;
+    let mut json = false;
+    let mut title = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => {
+                let value = args.next().ok_or(format!("Missing value for {arg}"))?;
+                parts = Some(parse_part(&value)?);
+            }
+            "-i" | "--input" => {
+                let value = args.next().ok_or(format!("Missing value for {arg}"))?;
+                input = Some(PathBuf::from(value));
+            }
+            "-q" | "--quiet" => quiet = true,
+            "-r" | "--record" => record = true,
+            "-n" | "--runs" => {
+                let value = args.next().ok_or(format!("Missing value for {arg}"))?;
+                runs = Some(parse_count(&value, 1)?);
+            }
+            "-w" | "--warmup" => {
+                let value = args.next().ok_or(format!("Missing value for {arg}"))?;
+                warmup = Some(parse_count(&value, 0)?);
+            }
+            "--json" => json = true,
+            "-t" | "--title" => {
+                title = Some(args.next().ok_or(format!("Missing value for {arg}"))?);
+            }
+            "-h" | "--help" => {
+                return Ok(Args {
+                    command: Command::Help,
+                    day_nums: vec![],
+                    parts: vec![],
+                    input: None,
+                    quiet,
+                    record,
+                    runs: DEFAULT_RUNS,
+                    warmup: DEFAULT_WARMUP,
+                    json,
+                    title,
+                })
+            }
+            s if s.starts_with('-') => return Err(format!("Unknown option `{s}`")),
+            _ => positionals.push(arg),
+        }
+    }
+
+    // The old `aoc2023 <day> [part]` form passes the part as a second positional argument
+    if legacy && positionals.len() == 2 && parts.is_none() {
+        parts = Some(parse_part(&positionals.pop().unwrap())?);
+    }
+
+    let day_nums = match (command, positionals.as_slice()) {
+        (Command::List | Command::Help, []) => vec![],
+        (Command::Run | Command::New, []) => return Err("No day number given".to_string()),
+        (Command::Bench | Command::Check, []) => implemented_days(),
+        (Command::New, [day]) => match day.parse::<i32>() {
+            Ok(n) if n > 0 && n <= LAST_DAY => vec![n],
+            _ => return Err(format!("Invalid day number {day}")),
+        },
+        (Command::Run | Command::Bench | Command::Check, [days]) => parse_day_nums(days)?,
+        (_, [_, extra, ..]) | (Command::List | Command::Help, [extra]) => return Err(format!("Unexpected argument `{extra}`")),
+    };
+
+    if input.is_some() && day_nums.len() != 1 {
+        return Err("--input can only be used with a single day".to_string());
+    }
+    if record && input.is_some() {
+        return Err("--record only records answers for the default inputs, it can't be used with --input".to_string());
+    }
+    if record && !matches!(command, Command::Run | Command::Check) {
+        return Err("--record can only be used with `run` and `check`".to_string());
+    }
+    if (runs.is_some() || warmup.is_some() || json) && command != Command::Bench {
+        return Err("--runs, --warmup and --json can only be used with `bench`".to_string());
+    }
+    if title.is_some() && command != Command::New {
+        return Err("--title can only be used with `new`".to_string());
+    }
+
+    Ok(Args {
+        command,
+        day_nums,
+        parts: parts.unwrap_or(vec![1, 2]),
+        input,
+        quiet,
+        record,
+        runs: runs.unwrap_or(DEFAULT_RUNS),
+        warmup: warmup.unwrap_or(DEFAULT_WARMUP),
+        json,
+        title,
+    })
+}
+
+fn parse_part(arg: &str) -> Result<Vec<i32>, String> {
+    match arg.parse::<i32>() {
+        Ok(n) if n == 1 || n == 2 => Ok(vec![n]),
+        _ => Err(format!("Invalid part number {arg}")),
+    }
+}
+
+fn parse_count(arg: &str, min: usize) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!("Invalid count {arg}, expected a number of at least {min}")),
+    }
+}
+
+fn implemented_days() -> Vec<i32> {
+    DAYS.iter().map(|day| day.number).collect()
+}
+
+fn parse_day_nums(arg: &str) -> Result<Vec<i32>, String> {
+    if arg == "all" {
+        return Ok(implemented_days());
+    }
+    let parse_num = |s: &str| match s.trim().parse::<i32>() {
+        Ok(n) if n > 0 && n <= LAST_DAY => Ok(n),
+        _ => Err(format!("Invalid day number {s}")),
+    };
+    let (start, end) = if let Some((start, end)) = arg.split_once("..=") {
+        (parse_num(start)?, parse_num(end)?)
+    } else if let Some((start, end)) = arg.split_once("..") {
+        (parse_num(start)?, parse_num(end)? - 1)
+    } else {
+        let num = parse_num(arg)?;
+        (num, num)
+    };
+    if start > end {
+        return Err(format!("Empty day range {arg}"));
+    }
+    Ok((start..=end).collect())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn parse(s: &str) -> Result<Args, String> {
+        parse_args(s.split_ascii_whitespace().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let args = parse("run 3..=5 --part 2 -q").unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.day_nums, vec![3, 4, 5]);
+        assert_eq!(args.parts, vec![2]);
+        assert!(args.quiet);
+
+        let args = parse("bench").unwrap();
+        assert_eq!(args.command, Command::Bench);
+        assert_eq!(args.day_nums, implemented_days());
+        assert_eq!((args.runs, args.warmup, args.json), (DEFAULT_RUNS, DEFAULT_WARMUP, false));
+
+        let args = parse("bench 23 -n 20 --warmup 0 --json").unwrap();
+        assert_eq!((args.runs, args.warmup, args.json), (20, 0, true));
+
+        let args = parse("run 7 --input inputs/other.txt").unwrap();
+        assert_eq!(args.input, Some(PathBuf::from("inputs/other.txt")));
+
+        let args = parse("12 1").unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.day_nums, vec![12]);
+        assert_eq!(args.parts, vec![1]);
+
+        assert!(parse("check --record").unwrap().record);
+
+        let args = parse("new 25 --title Snowverload").unwrap();
+        assert_eq!((args.command, args.day_nums, args.title), (Command::New, vec![25], Some("Snowverload".to_string())));
+
+        assert_eq!(parse("list --help").unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("frobnicate").is_err());
+        assert!(parse("run").is_err());
+        assert!(parse("run 0").is_err());
+        assert!(parse("run 30").is_err());
+        assert!(parse("run 5..3").is_err());
+        assert!(parse("run 5 --part 3").is_err());
+        assert!(parse("run 5 --part").is_err());
+        assert!(parse("run all --input foo.txt").is_err());
+        assert!(parse("run 5 --verbose").is_err());
+        assert!(parse("list 5").is_err());
+        assert!(parse("bench 5 --record").is_err());
+        assert!(parse("run 5 --record --input foo.txt").is_err());
+        assert!(parse("bench 5 --runs 0").is_err());
+        assert!(parse("run 5 --json").is_err());
+        assert!(parse("new 26").is_err());
+        assert!(parse("run 5 --title x").is_err());
+    }
+}
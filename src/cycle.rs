@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence of states, each computed from the previous one, starts repeating: the state after
+/// `start + length` steps is the same as after `start` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as after `step` steps.
+    pub fn reduce(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// States from the start of a sequence up to where it starts repeating, found by [`find_cycle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    /// The states after 0 to `start + length - 1` steps.
+    pub states: Vec<S>,
+}
+
+impl<S> History<S> {
+    /// The state after `step` steps, which can be way past the history.
+    pub fn state_at(&self, step: usize) -> &S {
+        &self.states[self.cycle.reduce(step)]
+    }
+}
+
+/// Finds the cycle by remembering every state until one repeats. Keeps the states, so any later state can be
+/// looked up without stepping again.
+pub fn find_cycle<S: Clone + Eq + Hash>(initial: S, mut step: impl FnMut(&S) -> S) -> History<S> {
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+    loop {
+        let next = step(states.last().unwrap());
+        if let Some(&start) = seen.get(&next) {
+            let length = states.len() - start;
+            return History {
+                cycle: Cycle { start, length },
+                states,
+            };
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+/// Finds the cycle with Floyd's tortoise and hare, keeping only a few states at a time.
+pub fn floyd<S: PartialEq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    // Find a multiple of the cycle length, where the hare has taken twice as many steps as the tortoise
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // Starting from the beginning and from that multiple in step, the states first match at the start of the cycle
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Finds the cycle with Brent's algorithm, which keeps only a few states like [`floyd`] but takes fewer steps.
+pub fn brent<S: PartialEq + Clone>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    // Find the length by letting the hare run ahead for increasing powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare a cycle length ahead, the states first match at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3 -> ...
+    fn step(&n: &u32) -> u32 {
+        if n == 6 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let history = find_cycle(0, step);
+        assert_eq!(history.cycle, Cycle { start: 3, length: 4 });
+        assert_eq!(history.states, [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(*history.state_at(2), 2);
+        assert_eq!(*history.state_at(7), 3);
+        assert_eq!(*history.state_at(1_000_000_001), 5);
+
+        let history = find_cycle(5, |&n| n);
+        assert_eq!(history.cycle, Cycle { start: 0, length: 1 });
+        assert_eq!(*history.state_at(10), 5);
+    }
+
+    #[test]
+    fn test_floyd_and_brent() {
+        let cycle = Cycle { start: 3, length: 4 };
+        assert_eq!(floyd(0, step), cycle);
+        assert_eq!(brent(0, step), cycle);
+        assert_eq!(cycle.reduce(1_000_000_000), 4);
+
+        let pure_cycle = Cycle { start: 0, length: 4 };
+        assert_eq!(floyd(3, step), pure_cycle);
+        assert_eq!(brent(3, step), pure_cycle);
+        assert_eq!(floyd(6, |&n| n), Cycle { start: 0, length: 1 });
+        assert_eq!(brent(6, |&n| n), Cycle { start: 0, length: 1 });
+    }
+}
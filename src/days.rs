@@ -1,31 +1,106 @@
 
-pub mod day_01;
-pub mod day_02;
-pub mod day_03;
-pub mod day_04;
-pub mod day_05;
-pub mod day_06;
-pub mod day_07;
-pub mod day_08;
-pub mod day_09;
-pub mod day_10;
-pub mod day_11;
-pub mod day_12;
-pub mod day_13;
-pub mod day_14;
-pub mod day_15;
-pub mod day_16;
-pub mod day_17;
-pub mod day_18;
-pub mod day_19;
-pub mod day_20;
-pub mod day_21;
-pub mod day_22;
-pub mod day_23;
-pub mod day_24;
+use crate::{Answer, Error};
+
+/// Declares the day modules and registers them in [`DAYS`]. Each module defines its puzzle `TITLE` and a `Day`
+/// implementing [`Problem`].
+macro_rules! days {
+    ($($number:literal => $module:ident,)*) => {
+        $(pub mod $module;)*
+
+        pub const DAYS: &[DayInfo] = &[
+            $(DayInfo { number: $number, title: $module::TITLE, problem: &$module::Day },)*
+        ];
+    };
+}
+
+days! {
+    1 => day_01,
+    2 => day_02,
+    3 => day_03,
+    4 => day_04,
+    5 => day_05,
+    6 => day_06,
+    7 => day_07,
+    8 => day_08,
+    9 => day_09,
+    10 => day_10,
+    11 => day_11,
+    12 => day_12,
+    13 => day_13,
+    14 => day_14,
+    15 => day_15,
+    16 => day_16,
+    17 => day_17,
+    18 => day_18,
+    19 => day_19,
+    20 => day_20,
+    21 => day_21,
+    22 => day_22,
+    23 => day_23,
+    24 => day_24,
+}
+
+#[cfg(test)]
 mod day_template;
 
+pub struct DayInfo {
+    pub number: i32,
+    pub title: &'static str,
+    pub problem: &'static dyn Problem,
+}
+
+impl DayInfo {
+    /// Solves `part` of the puzzle, attaching the day to any error. Parts other than 1 and 2 are an error.
+    pub fn solve(&self, part: i32, input: &str) -> Result<Answer, Error> {
+        let answer = match part {
+            1 => self.problem.part_one(input),
+            2 => self.problem.part_two(input),
+            _ => Err(Error::no_solution(format!("there is no part {part}, only parts 1 and 2"))),
+        };
+        answer.map_err(|err| err.with_day(self.number))
+    }
+}
+
+pub fn get_day(day_num: i32) -> Option<&'static DayInfo> {
+    DAYS.iter().find(|day| day.number == day_num)
+}
+
 pub trait Problem {
-    fn part_one(&self, input: &str) -> String;
-    fn part_two(&self, input: &str) -> String;
+    fn part_one(&self, input: &str) -> Result<Answer, Error>;
+    fn part_two(&self, input: &str) -> Result<Answer, Error>;
+
+    /// Only parses the input, so that the benchmarks can time parsing separately from solving. `None` for days
+    /// that parse the input as they go.
+    fn parse(&self, _input: &str) -> Option<Result<(), Error>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        assert!(DAYS.windows(2).all(|days| days[0].number < days[1].number));
+        assert!(DAYS.iter().all(|day| !day.title.is_empty()));
+        assert_eq!(get_day(7).map(|day| day.title), Some("Camel Cards"));
+        assert!(get_day(0).is_none());
+    }
+
+    #[test]
+    fn test_template_registers() {
+        // New days are created from the template, so it has to provide what `days!` expects
+        let template = DayInfo {
+            number: 0,
+            title: day_template::TITLE,
+            problem: &day_template::Day,
+        };
+        assert_eq!(template.solve(1, "x"), Ok(Answer::from("x")));
+        assert_eq!(
+            template.solve(3, "x"),
+            Err(Error::no_solution("there is no part 3, only parts 1 and 2").with_day(template.number))
+        );
+    }
 }
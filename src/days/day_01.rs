@@ -1,79 +1,68 @@
 
+use crate::aho_corasick::AhoCorasick;
+use crate::{input_lines, Answer, Error};
+
 use super::Problem;
 
 
-const NUM_STRS: [&str; 18] = [
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    "1", "2", "3", "4", "5", "6", "7", "8", "9"
+/// The digits written as digits.
+pub const DIGITS: [(&str, u32); 9] = [
+    ("1", 1), ("2", 2), ("3", 3), ("4", 4), ("5", 5), ("6", 6), ("7", 7), ("8", 8), ("9", 9)
+];
+
+/// The digits spelled out in English.
+pub const ENGLISH_WORDS: [(&str, u32); 9] = [
+    ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9)
 ];
 
+pub const TITLE: &str = "Trebuchet?!";
+
 pub struct Day;
 
 impl Problem for Day {
 
-    fn part_one(&self, input: &str) -> String {
-        let mut value_sum = 0;
-        for line in input.lines() {
-            let line = line.trim();
-            let nums: Vec<char> = line.chars().filter(|c| c.is_numeric()).collect();
-            let first_digit = nums.first().unwrap();
-            let last_digit = nums.last().unwrap();
-            let value = format!("{first_digit}{last_digit}").parse::<i32>().unwrap();
-            value_sum += value;
-        }
-        println!("Value sum: {value_sum}");
-        format!("{value_sum}")
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        calibration_sum(input, &DigitScanner::new(&DIGITS))
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let mut value_sum = 0;
-        for line in input.lines() {
-            let line = line.trim();
-            let (first_digit, last_digit) = find_first_last(line);
-            let value = format!("{first_digit}{last_digit}").parse::<i32>().unwrap();
-            value_sum += value;
-        }
-        println!("Value sum: {value_sum}");
-        format!("{value_sum}")
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        calibration_sum(input, &DigitScanner::new(&[DIGITS, ENGLISH_WORDS].concat()))
     }
 
 }
 
-fn find_first_last(line: &str) -> (i32, i32) {
-    let mut nums_positions: Vec<(usize, i32)> = Vec::new();
-    for search_str in NUM_STRS {
-        let num = str_into_num(search_str);
-        for m in line.match_indices(search_str){
-            nums_positions.push((m.0, num));
-        }
+/// Sum of the two-digit values made of the first and the last digit on each line.
+fn calibration_sum(input: &str, scanner: &DigitScanner) -> Result<Answer, Error> {
+    let mut value_sum = 0;
+    for line in input_lines(input) {
+        let (first_digit, last_digit) = scanner.first_last(line.text).ok_or(line.error(line.text, "no digits on line"))?;
+        value_sum += 10 * first_digit + last_digit;
     }
-    nums_positions.sort();
-    let first_digit = nums_positions[0].1;
-    let last_digit = nums_positions.pop().unwrap().1;
-    (first_digit, last_digit)
+    Ok(value_sum.into())
+}
+
+/// Finds digits on a line written with any of the words in a vocabulary. The words can overlap, like the
+/// `eight` and `two` in `eightwo`.
+pub struct DigitScanner {
+    matcher: AhoCorasick,
+    values: Vec<u32>,
 }
 
-fn str_into_num(s: &str) -> i32 {
-    match s {
-        "one"   => 1,
-        "two"   => 2,
-        "three" => 3,
-        "four"  => 4,
-        "five"  => 5,
-        "six"   => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine"  => 9,
-        "1"     => 1,
-        "2"     => 2,
-        "3"     => 3,
-        "4"     => 4,
-        "5"     => 5,
-        "6"     => 6,
-        "7"     => 7,
-        "8"     => 8,
-        "9"     => 9,
-        _       => panic!()
+impl DigitScanner {
+    /// Scanner for a vocabulary of words and the digits they stand for.
+    pub fn new(vocabulary: &[(&str, u32)]) -> Self {
+        DigitScanner {
+            matcher: AhoCorasick::new(vocabulary.iter().map(|(word, _)| word)),
+            values: vocabulary.iter().map(|&(_, value)| value).collect(),
+        }
+    }
+
+    /// The first and the last digit on the line, by where they start.
+    pub fn first_last(&self, line: &str) -> Option<(u32, u32)> {
+        let matches: Vec<_> = self.matcher.find_overlapping(line.as_bytes()).collect();
+        let first = matches.iter().min_by_key(|m| m.start)?;
+        let last = matches.iter().max_by_key(|m| m.start)?;
+        Some((self.values[first.pattern], self.values[last.pattern]))
     }
 }
 
@@ -85,45 +74,41 @@ mod tests {
     use rand::SeedableRng;
     use rand::rngs::StdRng;
 
-    #[test]
-    fn test_part_one() {
-        let input = "1abc2
-            pqr3stu8vwx
-            a1b2c3d4e5f
-            treb7uchet";
-        let value = Day.part_one(input);
-        assert_eq!(value, "142");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "two1nine
-            eightwothree
-            abcone2threexyz
-            xtwone3four
-            4nineeightseven2
-            zoneight234
-            7pqrstsixteen";
-        let value = Day.part_two(input);
-        assert_eq!(value, "281");
-    }
-
     #[test]
     fn test_find_first_last_random() {
+        let vocabulary = [DIGITS, ENGLISH_WORDS].concat();
+        let scanner = DigitScanner::new(&vocabulary);
         let mut rng = StdRng::seed_from_u64(0);
         for _ in 0..100 {
             let mut line = String::new();
             let mut nums = Vec::new();
             for _ in 0..5 {
-                let s = NUM_STRS.choose(&mut rng).unwrap().to_owned();
-                let num = str_into_num(s);
+                let (s, num) = vocabulary.choose(&mut rng).unwrap().to_owned();
                 line.push_str(s);
                 nums.push(num);
             }
-            let (first_digit, last_digit) = find_first_last(&line);
+            let (first_digit, last_digit) = scanner.first_last(&line).unwrap();
             assert_eq!(first_digit, nums[0]);
             assert_eq!(last_digit, nums[4]);
         }
     }
 
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = DigitScanner::new(&ENGLISH_WORDS);
+        assert_eq!(scanner.first_last("xtwone3"), Some((2, 1)));
+        assert_eq!(scanner.first_last("eightwo"), Some((8, 2)));
+        assert_eq!(scanner.first_last("1234"), None);
+
+        let finnish = [("yksi", 1), ("kaksi", 2), ("kolme", 3), ("seitsemän", 7), ("yhdeksän", 9)];
+        let scanner = DigitScanner::new(&[DIGITS.as_slice(), &finnish].concat());
+        assert_eq!(scanner.first_last("seitsemänkaksiyhdeksän"), Some((7, 9)));
+        assert_eq!(scanner.first_last("ykolmeksi4"), Some((3, 4)));
+    }
+
+    #[test]
+    fn test_non_ascii_numerals() {
+        assert_eq!(Day.part_one("a٣b"), Err(Error::parse(1, 1, "no digits on line")));
+        assert_eq!(Day.part_one("x٣7y2"), Ok(72.into()));
+    }
 }
@@ -1,87 +1,138 @@
+use std::collections::BTreeMap;
 
-use std::str::FromStr;
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
 
-struct GameSet {
-    red: usize,
-    green: usize,
-    blue: usize
+/// Numbers of cubes by colour.
+pub type Cubes<'a> = BTreeMap<&'a str, usize>;
+
+/// One game: the cubes shown in each reveal from the bag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game<'a> {
+    pub id: usize,
+    pub reveals: Vec<Cubes<'a>>,
 }
 
-impl FromStr for GameSet {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-        for cubes in s.split(",") {
-            let split: Vec<&str> = cubes.trim().split(" ").collect();
-            let num = match split[0].parse::<usize>() {
-                Ok(n) => n,
-                Err(_) => return Err(())
-            };
-            let color = split[1];
-            match color {
-                "red"   => red = num,
-                "green" => green = num,
-                "blue"  => blue = num,
-                _ => return Err(())
-            }
+impl<'a> Game<'a> {
+    /// Parses `Game <id>: <n> <colour>, ...; ...`.
+    pub fn parse(line: &Line<'a>) -> Result<Self, Error> {
+        let (label, reveals) = line.key_value(line.text, ":")?;
+        let (game, id) = line.key_value(label, " ")?;
+        if game != "Game" {
+            return Err(line.error(game, format!("expected `Game`, found `{game}`")));
         }
-        Ok(GameSet { red, green, blue })
+        let id = line.parse(id, "a game id")?;
+        let reveals = reveals.split(';').map(|reveal| parse_cubes(line, reveal)).collect::<Result<_, _>>()?;
+        Ok(Game { id, reveals })
+    }
+
+    /// Whether every reveal could have come from `bag`. Colours missing from the bag have no cubes.
+    pub fn is_feasible(&self, bag: &Cubes<'_>) -> bool {
+        self.reveals
+            .iter()
+            .all(|reveal| reveal.iter().all(|(colour, &num)| num <= bag.get(colour).copied().unwrap_or(0)))
+    }
+
+    /// The fewest cubes of each colour that make the game feasible.
+    pub fn minimal_bag(&self) -> Cubes<'a> {
+        let mut bag = Cubes::new();
+        for (&colour, &num) in self.reveals.iter().flatten() {
+            let max = bag.entry(colour).or_insert(0);
+            *max = (*max).max(num);
+        }
+        bag
+    }
+
+    /// Product of the numbers of cubes of `colours` in the minimal bag, which is zero if a colour is never seen.
+    pub fn power(&self, colours: &[&str]) -> usize {
+        let bag = self.minimal_bag();
+        colours.iter().map(|colour| bag.get(colour).copied().unwrap_or(0)).product()
     }
 }
 
-pub struct Day;
+fn parse_cubes<'a>(line: &Line<'a>, reveal: &'a str) -> Result<Cubes<'a>, Error> {
+    let mut cubes = Cubes::new();
+    for item in reveal.split(',') {
+        let (num, colour) = line.key_value(item.trim(), " ")?;
+        let num: usize = line.parse(num, "a number of cubes")?;
+        *cubes.entry(colour).or_insert(0) += num;
+    }
+    Ok(cubes)
+}
 
-impl Problem for Day {
+/// How the cubes of one colour show up across a log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColourStats {
+    /// Number of games where the colour is seen.
+    pub games: usize,
+    /// Number of reveals where the colour is seen.
+    pub reveals: usize,
+    /// Cubes shown over all reveals.
+    pub total: usize,
+    /// Most cubes shown at once.
+    pub max: usize,
+}
+
+/// All the games played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameLog<'a> {
+    pub games: Vec<Game<'a>>,
+}
+
+impl<'a> GameLog<'a> {
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let games = input_lines(input).iter().map(Game::parse).collect::<Result<_, _>>()?;
+        Ok(GameLog { games })
+    }
 
-    fn part_one(&self, input: &str) -> String {
-        let mut id_sum = 0;
-        let mut id_game = 0;
-        for line in input.trim().lines() {
-            let line = line.trim();
-            if line.len() == 0 {
-                continue;
+    pub fn feasible_games<'b>(&'b self, bag: &'b Cubes<'_>) -> impl Iterator<Item = &'b Game<'a>> + 'b {
+        self.games.iter().filter(|game| game.is_feasible(bag))
+    }
+
+    pub fn colour_stats(&self) -> BTreeMap<&'a str, ColourStats> {
+        let mut stats: BTreeMap<&str, ColourStats> = BTreeMap::new();
+        for game in &self.games {
+            for (colour, max) in game.minimal_bag() {
+                let colour_stats = stats.entry(colour).or_default();
+                colour_stats.games += 1;
+                colour_stats.max = colour_stats.max.max(max);
             }
-            id_game += 1;
-            let (max_red, max_green, max_blue) = find_max_nums(line);
-            if max_red <= 12 && max_green <= 13 && max_blue <= 14 {
-                id_sum += id_game;
+            for (&colour, &num) in game.reveals.iter().flatten() {
+                let colour_stats = stats.entry(colour).or_default();
+                colour_stats.reveals += 1;
+                colour_stats.total += num;
             }
-            
         }
-        println!("{id_sum}");
-        format!("{id_sum}")
+        stats
     }
+}
 
-    fn part_two(&self, input: &str) -> String {
-        let mut power_total = 0;
-        for line in input.trim().lines() {
-            let line = line.trim();
-            if line.len() == 0 {
-                continue;
-            }
-            let (max_red, max_green, max_blue) = find_max_nums(line);
-            let power = max_red * max_green * max_blue;
-            power_total += power;
-        }
-        println!("{power_total}");
-        format!("{power_total}")
+pub const TITLE: &str = "Cube Conundrum";
+
+pub struct Day;
+
+impl Problem for Day {
+
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let bag = Cubes::from([("red", 12), ("green", 13), ("blue", 14)]);
+        let id_sum: usize = GameLog::parse(input)?.feasible_games(&bag).map(|game| game.id).sum();
+        Ok(id_sum.into())
     }
 
-}
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let power_total: usize = GameLog::parse(input)?
+            .games
+            .iter()
+            .map(|game| game.power(&["red", "green", "blue"]))
+            .sum();
+        Ok(power_total.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(GameLog::parse(input).map(|_| ()))
+    }
 
-fn find_max_nums(s: &str) -> (usize, usize, usize) {
-    let game_sets: Vec<GameSet> = s
-        .split(":").last().unwrap()
-        .split(";").into_iter().map(|s| s.parse().unwrap())
-        .collect();
-    let max_red = game_sets.iter().map(|g| g.red).max().unwrap();
-    let max_green = game_sets.iter().map(|g| g.green).max().unwrap();
-    let max_blue = game_sets.iter().map(|g| g.blue).max().unwrap();
-    (max_red, max_green, max_blue)
 }
 
 #[cfg(test)]
@@ -89,28 +140,33 @@ mod tests {
 
     use super::*;
 
+    const LOG: &str = "
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 7: 2 purple; 1 red, 1 purple";
+
     #[test]
-    fn test_part_one() {
-        let input = "
-            Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-            Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-            Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-            Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        let value = Day.part_one(input);
-        assert_eq!(value, "8");
+    fn test_games() {
+        let log = GameLog::parse(LOG).unwrap();
+        assert_eq!(log.games[2].id, 7);
+        assert_eq!(log.games[0].minimal_bag(), Cubes::from([("red", 4), ("green", 2), ("blue", 6)]));
+        assert_eq!(log.games[1].power(&["red", "green", "blue"]), 12);
+        assert_eq!(log.games[2].power(&["red", "green", "blue"]), 0);
+
+        let bag = Cubes::from([("red", 4), ("green", 3), ("blue", 6)]);
+        let ids: Vec<usize> = log.feasible_games(&bag).map(|game| game.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(log.games[2].is_feasible(&Cubes::from([("red", 1), ("purple", 2)])));
     }
 
     #[test]
-    fn test_part_two() {
-        let input = "
-            Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-            Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-            Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-            Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        let value = Day.part_two(input);
-        assert_eq!(value, "2286");
-    }
+    fn test_colour_stats() {
+        let stats = GameLog::parse(LOG).unwrap().colour_stats();
+        assert_eq!(stats["red"], ColourStats { games: 3, reveals: 4, total: 7, max: 4 });
+        assert_eq!(stats["purple"], ColourStats { games: 1, reveals: 2, total: 3, max: 2 });
+        assert_eq!(stats.len(), 4);
 
+        let line = Line::new(1, "Round 1: 3 blue");
+        assert_eq!(Game::parse(&line), Err(Error::parse(1, 1, "expected `Game`, found `Round`")));
+    }
 }
@@ -1,119 +1,146 @@
+use std::ops::Range;
+
+use crate::grid::{Grid, Pos};
+use crate::{input_lines, Answer, Error};
 
 use super::Problem;
 
-#[derive(Debug)]
-struct Number {
-    val: usize,
-    pos: Vec<(usize, usize)>
+/// A number in the schematic, on `row` at the columns `cols`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: usize,
+    pub row: usize,
+    pub cols: Range<usize>,
 }
 
-struct Part {
-    symbol: char,
-    pos: (usize, usize)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub pos: Pos,
 }
 
-pub struct Day;
-
-impl Problem for Day {
-
-    fn part_one(&self, input: &str) -> String {
-
-        let (numbers, parts) = find_numbers_parts(input);
+/// The engine schematic, with the numbers next to each symbol.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    /// Index of the number covering each tile.
+    number_index: Grid<Option<usize>>,
+    /// Indices of the numbers next to each symbol, in the order of `symbols`.
+    adjacency: Vec<Vec<usize>>,
+}
 
-        let mut vals_sum = 0;
-        for num in numbers {
-            let mut is_close = false;
-            for part in parts.iter() {
-                if num_part_distance(&num, &part) <= 1 {
-                    is_close = true;
-                    break;
+impl Schematic {
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let lines = input_lines(input);
+        let grid = Grid::from_lines(&lines, Some)?;
+
+        let mut numbers: Vec<Number> = Vec::new();
+        let mut symbols = Vec::new();
+        let mut number_index = Grid::filled(grid.width(), grid.height(), None);
+        for (row, line) in lines.iter().enumerate() {
+            let mut current: Option<Number> = None;
+            // Trailing '.' so that a number at the end of the line gets pushed before the next line starts
+            for (col, &c) in grid.row(row).iter().chain(&['.']).enumerate() {
+                if let Some(digit) = c.to_digit(10) {
+                    let number = current.get_or_insert(Number { value: 0, row, cols: col..col });
+                    number.value = number
+                        .value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit as usize))
+                        .ok_or_else(|| line.error_at(number.cols.start, "number is too large"))?;
+                    number.cols.end = col + 1;
+                    number_index[(row, col)] = Some(numbers.len());
+                    continue;
+                }
+                if let Some(number) = current.take() {
+                    numbers.push(number);
+                }
+                if c != '.' && col < grid.width() {
+                    symbols.push(Symbol { symbol: c, pos: (row, col) });
                 }
-            }
-            if is_close {
-                vals_sum += num.val;
             }
         }
-        
-        println!("{vals_sum}");
-        format!("{vals_sum}")
+
+        let adjacency = symbols
+            .iter()
+            .map(|symbol| {
+                let mut adjacent: Vec<usize> = number_index.neighbours8(symbol.pos).filter_map(|pos| number_index[pos]).collect();
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                adjacent
+            })
+            .collect();
+
+        Ok(Schematic { numbers, symbols, number_index, adjacency })
     }
 
-    fn part_two(&self, input: &str) -> String {
-        
-        let (numbers, parts) = find_numbers_parts(input);
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
 
-        let mut vals_sum = 0;
-        for part in parts {
-            if part.symbol != '*' {
-                continue;
-            }
-            let mut close_nums = Vec::new();
-            for num in numbers.iter() {
-                if num_part_distance(&num, &part) <= 1 {
-                    close_nums.push(num);
-                }
-            }
-            if close_nums.len() == 2 {
-                vals_sum += close_nums[0].val * close_nums[1].val;
-            }
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn number_at(&self, pos: Pos) -> Option<&Number> {
+        self.number_index.get(pos).copied().flatten().map(|index| &self.numbers[index])
+    }
+
+    /// The numbers next to the symbol with the index `symbol` in [`Schematic::symbols`].
+    pub fn adjacent_numbers(&self, symbol: usize) -> impl Iterator<Item = &Number> {
+        self.adjacency[symbol].iter().map(|&index| &self.numbers[index])
+    }
+
+    /// Numbers next to at least one symbol, in reading order.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        let mut is_part = vec![false; self.numbers.len()];
+        for &index in self.adjacency.iter().flatten() {
+            is_part[index] = true;
         }
+        self.numbers.iter().zip(is_part).filter_map(|(number, is_part)| is_part.then_some(number))
+    }
 
-        println!("{vals_sum}");
-        format!("{vals_sum}")
+    /// Symbols accepted by `filter` with the numbers next to them.
+    pub fn symbols_where<'a>(
+        &'a self,
+        filter: impl Fn(&Symbol) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a Symbol, Vec<&'a Number>)> + 'a {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(move |(_, symbol)| filter(symbol))
+            .map(|(index, symbol)| (symbol, self.adjacent_numbers(index).collect()))
     }
 
+    /// `*` symbols next to exactly `k` numbers.
+    pub fn gears(&self, k: usize) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols_where(|symbol| symbol.symbol == '*').filter(move |(_, numbers)| numbers.len() == k)
+    }
 }
 
-fn find_numbers_parts(input: &str) -> (Vec<Number>, Vec<Part>) {
+pub const TITLE: &str = "Gear Ratios";
 
-    let mut parts: Vec<Part> = Vec::new();
-    let mut numbers: Vec<Number> = Vec::new();
-    let mut num_char_buffer = String::new();
-    let mut num_pos_buffer = Vec::new();
+pub struct Day;
 
-    for (row, line) in input.trim().lines().enumerate() {
-        let line = line.trim();
-        if line.len() == 0 {
-            continue;
-        }
-        for (col, c) in line.chars().enumerate() {
-            match c {
-                x if x.is_numeric() => {
-                    num_char_buffer.push(x);
-                    num_pos_buffer.push((row, col))
-                },
-                x => {
-                    if num_char_buffer.len() > 0 {
-                        let val: usize = num_char_buffer.parse().unwrap();
-                        numbers.push( Number{ val, pos: num_pos_buffer.clone() } );
-                        num_char_buffer.clear();
-                        num_pos_buffer.clear();
-                    }
-                    match x {
-                        '.' => (),
-                        _ => parts.push( Part { symbol: x, pos: (row, col) } )
-                    }
-                    
-                }
-            }
-        }
-    }
+impl Problem for Day {
 
-    (numbers, parts)
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let schematic = Schematic::parse(input)?;
+        let vals_sum: usize = schematic.part_numbers().map(|num| num.value).sum();
+        Ok(vals_sum.into())
+    }
 
-}
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let schematic = Schematic::parse(input)?;
+        let vals_sum: usize = schematic.gears(2).map(|(_, nums)| nums[0].value * nums[1].value).sum();
+        Ok(vals_sum.into())
+    }
 
-fn num_part_distance(num: &Number, part: &Part) -> usize {
-    let sym_pos = part.pos;
-    let mut min_row_dist = usize::MAX;
-    let mut min_col_dist = usize::MAX;
-    for num_pos in num.pos.iter() {
-        let row_dist = sym_pos.0.abs_diff(num_pos.0);
-        let col_dist = sym_pos.1.abs_diff(num_pos.1);
-        min_row_dist = min_row_dist.min(row_dist);
-        min_col_dist = min_col_dist.min(col_dist);
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(Schematic::parse(input).map(|_| ()))
     }
-    min_row_dist.max(min_col_dist)
+
 }
 
 #[cfg(test)]
@@ -121,38 +148,39 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let input = "
+    const SCHEMATIC: &str = "
         467..114..
         ...*......
         ..35..633.
         ......#...
         617*......
-        .....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..";
-        let value = Day.part_one(input);
-        assert_eq!(value, "4361");
-    }
+        .....+.58.";
 
     #[test]
-    fn test_part_two() {
-        let input = "
-        467..114..
-        ...*......
-        ..35..633.
-        ......#...
-        617*......
-        .....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..";
-        let value = Day.part_two(input);
-        assert_eq!(value, "467835");
+    fn test_adjacency() {
+        let schematic = Schematic::parse(SCHEMATIC).unwrap();
+        assert_eq!(schematic.numbers().len(), 6);
+        assert_eq!(schematic.number_at((2, 7)), Some(&Number { value: 633, row: 2, cols: 6..9 }));
+        assert_eq!(schematic.number_at((1, 7)), None);
+
+        let parts: Vec<usize> = schematic.part_numbers().map(|num| num.value).collect();
+        assert_eq!(parts, vec![467, 35, 633, 617]);
+        let stars: Vec<Vec<usize>> = schematic
+            .symbols_where(|symbol| symbol.symbol == '*')
+            .map(|(_, nums)| nums.iter().map(|num| num.value).collect())
+            .collect();
+        assert_eq!(stars, vec![vec![467, 35], vec![617]]);
     }
 
+    #[test]
+    fn test_gears() {
+        let schematic = Schematic::parse(SCHEMATIC).unwrap();
+        let gears: Vec<Pos> = schematic.gears(2).map(|(symbol, _)| symbol.pos).collect();
+        assert_eq!(gears, vec![(1, 3)]);
+        assert_eq!(schematic.gears(1).count(), 1);
+        assert_eq!(schematic.gears(3).count(), 0);
+
+        let too_large = Schematic::parse("..99999999999999999999+").unwrap_err();
+        assert_eq!(too_large, Error::parse(1, 3, "number is too large"));
+    }
 }
@@ -1,100 +1,216 @@
+use std::ops::Range;
+
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
-use aoc2023::filter_input_lines;
 
-pub struct Day;
+/// A card with its winning numbers and the numbers we have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scratchcard {
+    pub id: usize,
+    pub winning: Vec<usize>,
+    pub numbers: Vec<usize>,
+}
 
-impl Problem for Day {
+impl Scratchcard {
+    /// Parses `Card <id>: <winning numbers> | <numbers>`.
+    pub fn parse(line: &Line) -> Result<Self, Error> {
+        let (label, numbers) = line.key_value(line.text, ":")?;
+        let (card, id) = line.key_value(label, " ")?;
+        if card != "Card" {
+            return Err(line.error(card, format!("expected `Card`, found `{card}`")));
+        }
+        let id = line.parse(id, "a card number")?;
+        let (winning, numbers) = line.split_once(numbers, "|")?;
+        Ok(Scratchcard {
+            id,
+            winning: line.parse_words(winning, "a number")?,
+            numbers: line.parse_words(numbers, "a number")?,
+        })
+    }
 
-    fn part_one(&self, input: &str) -> String {
-        let mut total_points = 0;
-        let input_lines = filter_input_lines(input);
-        for line in input_lines {
-            let line = line.trim();
-            if line.len() == 0 {
-                continue;
-            }
-            let card_nums: Vec<&str> = line.split(":").last().unwrap().split("|").collect();
-            let winning_nums: Vec<usize> = card_nums[0].split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-            let player_nums: Vec<usize> = card_nums[1].split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-            let mut points = 0;
-            for num in player_nums {
-                if winning_nums.contains(&num) {
-                    if points == 0 {
-                        points = 1;
-                    }
-                    else {
-                        points = points * 2;
-                    }
-                }
+    /// How many of our numbers are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.numbers.iter().filter(|num| self.winning.contains(num)).count()
+    }
+}
+
+/// Parses the table of cards, which have to be numbered from 1 in order.
+pub fn parse_cards(input: &str) -> Result<Vec<Scratchcard>, Error> {
+    input_lines(input)
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let card = Scratchcard::parse(line)?;
+            if card.id != index + 1 {
+                return Err(line.error(line.text, format!("expected card {}, found card {}", index + 1, card.id)));
             }
-            total_points += points;
+            Ok(card)
+        })
+        .collect()
+}
+
+/// How cards are scored and how they win copies of other cards. Each function gets the number of matches on
+/// the card.
+#[derive(Debug, Clone, Copy)]
+pub struct CascadeRules {
+    /// Points of the card, or `None` if they do not fit in a `usize`.
+    pub score: fn(usize) -> Option<usize>,
+    /// How many of the following cards get a copy for each copy of the card.
+    pub cards_won: fn(usize) -> usize,
+    /// Most copies of a single card, or `None` for no limit.
+    pub max_copies: Option<usize>,
+}
+
+impl Default for CascadeRules {
+    /// The rules of the puzzle: a point for the first match, doubled for every further match, and a card for
+    /// every match.
+    fn default() -> Self {
+        CascadeRules {
+            score: |matches| match matches {
+                0 => Some(0),
+                _ => 1usize.checked_shl(u32::try_from(matches - 1).ok()?),
+            },
+            cards_won: |matches| matches,
+            max_copies: None,
         }
-        println!("{total_points}");
-        format!("{total_points}")
     }
+}
 
-    fn part_two(&self, input: &str) -> String {
+impl CascadeRules {
+    pub fn points(&self, card: &Scratchcard) -> Result<usize, Error> {
+        (self.score)(card.matches()).ok_or_else(|| Error::no_solution(format!("the points of card {} do not fit", card.id)))
+    }
+
+    pub fn total_points(&self, cards: &[Scratchcard]) -> Result<usize, Error> {
+        cards.iter().try_fold(0usize, |total, card| {
+            total.checked_add(self.points(card)?).ok_or_else(|| Error::no_solution("the total points do not fit"))
+        })
+    }
+}
 
-        let input_lines = filter_input_lines(input);
-        let num_cards = input_lines.len();
-        let mut card_counts = vec![0; num_cards];
+/// One card handing out copies of the following cards during a [`cascade`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CascadeStep {
+    pub card: usize,
+    /// Copies of the card, which is also how many copies each of the won cards gets.
+    pub copies: usize,
+    /// Ids of the cards won.
+    pub won: Range<usize>,
+}
 
-        for line in input_lines {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+    /// Copies of each card in the end, originals included, in the order of the cards.
+    pub copies: Vec<usize>,
+    pub trace: Vec<CascadeStep>,
+}
 
-            let (card_num, numbers) = line.split_once(":").unwrap();
-            let card_num: usize = card_num.trim().split_ascii_whitespace().last().unwrap().parse().unwrap();
-            let (winning_nums, player_nums) = numbers.split_once("|").unwrap();
-            let player_nums: Vec<usize> = player_nums.split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-            let winning_nums: Vec<usize> = winning_nums.split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-            let matching_nums = player_nums.iter().filter(|num| winning_nums.contains(&num)).collect::<Vec<&usize>>().len();
+impl Cascade {
+    pub fn total_cards(&self) -> usize {
+        self.copies.iter().sum()
+    }
+}
 
-            card_counts[card_num - 1] += 1;
-            for i in 0..matching_nums {
-                card_counts[card_num + i] += card_counts[card_num - 1];
+/// Plays the cards in order, each copy of a card winning copies of the cards after it.
+pub fn cascade(cards: &[Scratchcard], rules: &CascadeRules) -> Result<Cascade, Error> {
+    let mut copies: Vec<usize> = vec![1; cards.len()];
+    let mut trace = Vec::new();
+    for (index, card) in cards.iter().enumerate() {
+        let end = (index + 1).checked_add((rules.cards_won)(card.matches())).filter(|&end| end <= cards.len());
+        let Some(end) = end else {
+            return Err(Error::no_solution(format!(
+                "card {} wins copies of cards past the end of the table of {} cards",
+                card.id,
+                cards.len()
+            )));
+        };
+        let won = index + 1..end;
+        for next in won.clone() {
+            copies[next] = copies[next]
+                .checked_add(copies[index])
+                .ok_or_else(|| Error::no_solution(format!("the copies of card {} do not fit", cards[next].id)))?;
+            if let Some(max_copies) = rules.max_copies {
+                copies[next] = copies[next].min(max_copies);
             }
-
         }
+        trace.push(CascadeStep { card: card.id, copies: copies[index], won: won.start + 1..won.end + 1 });
+    }
+    Ok(Cascade { copies, trace })
+}
 
-        let total_card_count: usize = card_counts.iter().sum();
+pub const TITLE: &str = "Scratchcards";
 
-        println!("{total_card_count}");
-        format!("{total_card_count}")
-        
+pub struct Day;
+
+impl Problem for Day {
+
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let total_points = CascadeRules::default().total_points(&parse_cards(input)?)?;
+        Ok(total_points.into())
     }
 
-}
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let total_card_count = cascade(&parse_cards(input)?, &CascadeRules::default())?.total_cards();
+        Ok(total_card_count.into())
+    }
 
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(parse_cards(input).map(|_| ()))
+    }
+
+}
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    const CARDS: &str = "
+        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83";
+
     #[test]
-    fn test_part_one() {
-        let input = "
-            Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-            Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-            Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-            Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-            Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-            Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        let value = Day.part_one(input);
-        assert_eq!(value, "13");
+    fn test_cascade() {
+        let cards = parse_cards(CARDS).unwrap();
+        let matches: Vec<usize> = cards.iter().map(Scratchcard::matches).collect();
+        assert_eq!(matches, vec![4, 2, 2, 1]);
+        assert!(cascade(&cards, &CascadeRules::default()).is_err());
+
+        let rules = CascadeRules { cards_won: |matches| matches / 2, ..Default::default() };
+        let result = cascade(&cards, &rules).unwrap();
+        assert_eq!(result.copies, vec![1, 2, 4, 5]);
+        assert_eq!(result.trace[1], CascadeStep { card: 2, copies: 2, won: 3..4 });
+        assert_eq!(result.total_cards(), 12);
+
+        let capped = CascadeRules { max_copies: Some(3), ..rules };
+        assert_eq!(cascade(&cards, &capped).unwrap().copies, vec![1, 2, 3, 3]);
     }
 
     #[test]
-    fn test_part_two() {
-        let input = "
-            Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-            Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-            Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-            Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-            Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-            Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        let value = Day.part_two(input);
-        assert_eq!(value, "30");
+    fn test_parse_cards() {
+        let rules = CascadeRules { score: |matches| Some(matches), ..Default::default() };
+        let cards = parse_cards(CARDS).unwrap();
+        let points: Vec<usize> = cards.iter().map(|card| rules.points(card).unwrap()).collect();
+        assert_eq!(points, vec![4, 2, 2, 1]);
+        assert_eq!(CascadeRules::default().total_points(&cards), Ok(8 + 2 + 2 + 1));
+
+        let err = parse_cards("Card 1: 1 | 1\nCard 3: 2 | 2").unwrap_err();
+        assert_eq!(err, Error::parse(2, 1, "expected card 2, found card 3"));
     }
 
+    #[test]
+    fn test_many_matches() {
+        let card = |id, matches| Scratchcard { id, winning: (0..matches).collect(), numbers: (0..matches).collect() };
+        let rules = CascadeRules::default();
+        assert_eq!(rules.points(&card(1, 64)), Ok(1 << 63));
+        assert_eq!(rules.points(&card(2, 65)), Err(Error::no_solution("the points of card 2 do not fit")));
+        assert!(rules.total_points(&[card(1, 64), card(2, 64)]).is_err());
+
+        let rules = CascadeRules { cards_won: |_| usize::MAX, ..rules };
+        let err = Error::no_solution("card 1 wins copies of cards past the end of the table of 2 cards");
+        assert_eq!(cascade(&[card(1, 1), card(2, 1)], &rules), Err(err));
+    }
 }
@@ -1,181 +1,131 @@
-use aoc2023::filter_input_lines;
+use crate::interval::{Interval, IntervalSet, RangeMap};
+use crate::{input_blocks, Answer, Error, Line};
 
 use super::Problem;
 
-pub struct Day;
+/// A `<source>-to-<destination> map:` section of the almanac.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryMap<'a> {
+    pub source: &'a str,
+    pub destination: &'a str,
+    pub map: RangeMap,
+}
 
-impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-        let seeds: Vec<u64> = input[0]
-            .split(":")
-            .last()
-            .unwrap()
-            .trim()
-            .split_ascii_whitespace()
-            .map(|s| s.trim().parse().unwrap())
-            .collect();
-
-        let (
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location
-        ) = get_maps(input);
-
-        let mut min_location = u64::MAX;
-        for seed in seeds {
-            let soil = map_number(seed, &seed_to_soil);
-            let fertilizer = map_number(soil, &soil_to_fertilizer);
-            let water = map_number(fertilizer, &fertilizer_to_water);
-            let light = map_number(water, &water_to_light);
-            let temperature = map_number(light, &light_to_temperature);
-            let humidity = map_number(temperature, &temperature_to_humidity);
-            let location = map_number(humidity, &humidity_to_location);
-            min_location = min_location.min(location);
+/// The seeds and the maps from one category to the next, from `seed` to the last destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac<'a> {
+    pub seeds: Vec<i64>,
+    pub maps: Vec<CategoryMap<'a>>,
+    seeds_line: Line<'a>,
+    /// All the maps composed into one.
+    composed: RangeMap,
+}
+
+impl<'a> Almanac<'a> {
+    /// Parses the seeds and any number of maps, each starting from the destination of the one before.
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let blocks = input_blocks(input);
+        let (seeds_block, map_blocks) = blocks.split_first().ok_or(Error::end_of_input(input, "expected the seeds"))?;
+        let seeds_line = seeds_block[0];
+        let (label, seeds) = seeds_line.key_value(seeds_line.text, ":")?;
+        if label != "seeds" {
+            return Err(seeds_line.error(label, format!("expected `seeds`, found `{label}`")));
         }
+        let seeds = seeds_line.parse_words(seeds, "a seed number")?;
 
-        println!("{min_location}");
-        format!("{min_location}")
-    }
+        let mut maps: Vec<CategoryMap> = Vec::new();
+        for line in seeds_block[1..].iter().chain(map_blocks.iter().flatten()) {
+            if !line.text.starts_with(|c: char| c.is_ascii_digit()) {
+                maps.push(parse_header(line, maps.last().map_or("seed", |map| map.destination))?);
+                continue;
+            }
+            let Some(current) = maps.last_mut() else {
+                return Err(line.error(line.text, "expected a map header before the ranges"));
+            };
+            let [dest_start, source_start, range] = line.ints::<i64>(line.text)?[..] else {
+                return Err(line.error(line.text, "expected destination start, source start and range length"));
+            };
+            current.map.insert(Interval::with_len(source_start, range), dest_start - source_start)
+        }
 
-    fn part_two(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-
-        let seed_ranges: Vec<(u64, u64)> = input[0]
-            .split(":")
-            .last()
-            .unwrap()
-            .split_ascii_whitespace()
-            .collect::<Vec<&str>>()
-            .chunks(2)
-            .map(|chunk| {
-                let start: u64 = chunk[0].parse().unwrap();
-                let range: u64 = chunk[1].parse().unwrap();
-                (start, range)
-            })
-            .collect();
-
-        let (
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location
-        ) = get_maps(input);
-
-        let soil = map_ranges(seed_ranges, &seed_to_soil);
-        let fertilizer = map_ranges(soil, &soil_to_fertilizer);
-        let water = map_ranges(fertilizer, &fertilizer_to_water);
-        let light = map_ranges(water, &water_to_light);
-        let temperature = map_ranges(light, &light_to_temperature);
-        let humidity = map_ranges(temperature, &temperature_to_humidity);
-        let location = map_ranges(humidity, &humidity_to_location);
-    
-        let min_location = location.iter().min().unwrap().0;
-
-        println!("{min_location}");
-        format!("{min_location}")
+        let composed = maps.iter().fold(RangeMap::new(), |composed, map| composed.compose(&map.map));
+        Ok(Almanac { seeds, maps, seeds_line, composed })
     }
-}
 
-fn get_maps(input: Vec<&str>) -> (Vec<(u64, u64, u64)>, Vec<(u64, u64, u64)>, Vec<(u64, u64, u64)>, Vec<(u64, u64, u64)>, Vec<(u64, u64, u64)>, Vec<(u64, u64, u64)>, Vec<(u64, u64, u64)>) {
-    let mut seed_to_soil: Vec<(u64, u64, u64)> = Vec::new();
-    let mut soil_to_fertilizer: Vec<(u64, u64, u64)> = Vec::new();
-    let mut fertilizer_to_water: Vec<(u64, u64, u64)> = Vec::new();
-    let mut water_to_light: Vec<(u64, u64, u64)> = Vec::new();
-    let mut light_to_temperature: Vec<(u64, u64, u64)> = Vec::new();
-    let mut temperature_to_humidity: Vec<(u64, u64, u64)> = Vec::new();
-    let mut humidity_to_location: Vec<(u64, u64, u64)> = Vec::new();
-
-    let mut current_map = &mut seed_to_soil;
-
-    for line in input.into_iter().skip(2) {
-        if line.chars().next().unwrap().is_numeric() {
-            let nums: Vec<u64> = line
-                .split_ascii_whitespace()
-                .map(|s| s.trim().parse().unwrap())
-                .collect();
-            let dest_start = nums[0];
-            let source_start = nums[1];
-            let range = nums[2];
-            current_map.push((dest_start, source_start, range))
-        } else {
-            match line {
-                _ if line.contains("soil-to-fertilizer") => current_map = &mut soil_to_fertilizer,
-                _ if line.contains("fertilizer-to-water") => current_map = &mut fertilizer_to_water,
-                _ if line.contains("water-to-light") => current_map = &mut water_to_light,
-                _ if line.contains("light-to-temperature") => {
-                    current_map = &mut light_to_temperature
-                }
-                _ if line.contains("temperature-to-humidity") => {
-                    current_map = &mut temperature_to_humidity
-                }
-                _ if line.contains("humidity-to-location") => {
-                    current_map = &mut humidity_to_location
-                }
-                _ => panic!(""),
-            }
+    /// The seeds read as pairs of range starts and lengths.
+    pub fn seed_ranges(&self) -> Result<IntervalSet, Error> {
+        if !self.seeds.len().is_multiple_of(2) {
+            let line = self.seeds_line;
+            return Err(line.error(line.text, "expected pairs of seed range starts and lengths"));
         }
+        Ok(self.seeds.chunks(2).map(|chunk| Interval::with_len(chunk[0], chunk[1])).collect())
     }
 
-    (seed_to_soil, soil_to_fertilizer, fertilizer_to_water, water_to_light, light_to_temperature, temperature_to_humidity, humidity_to_location)
+    /// The map from seeds to the last category.
+    pub fn composed(&self) -> &RangeMap {
+        &self.composed
+    }
+
+    /// The map from category `from` to category `to`, or `None` if the maps do not lead from one to the other.
+    pub fn map_between(&self, from: &str, to: &str) -> Option<RangeMap> {
+        let start = self.maps.iter().position(|map| map.source == from)?;
+        let end = start + self.maps[start..].iter().position(|map| map.destination == to)?;
+        Some(self.maps[start..=end].iter().fold(RangeMap::new(), |composed, map| composed.compose(&map.map)))
+    }
+
+    /// The last category of a seed.
+    pub fn location(&self, seed: i64) -> i64 {
+        self.composed.get(seed)
+    }
 
+    /// The last categories of all the seeds in `seeds`.
+    pub fn locations(&self, seeds: &IntervalSet) -> IntervalSet {
+        self.composed.map_set(seeds)
+    }
+
+    /// All the seed numbers, listed or not, with their last category in `locations`.
+    pub fn seeds_for(&self, locations: &IntervalSet) -> IntervalSet {
+        self.composed.preimage(locations)
+    }
 }
 
-fn map_number(num: u64, maps: &Vec<(u64, u64, u64)>) -> u64 {
-    let mut mapped_num = num;
-    for map in maps {
-        let (dest_start, source_start, range) = map;
-        let source_diff = (num as i64) - (*source_start as i64);
-        if source_diff >= 0 && source_diff < (*range as i64) {
-            mapped_num = dest_start + (source_diff as u64);
-            break;
-        }
+/// Parses `<source>-to-<destination> map:`, where the source has to be `expected_source`.
+fn parse_header<'a>(line: &Line<'a>, expected_source: &str) -> Result<CategoryMap<'a>, Error> {
+    let header_format = || line.error(line.text, "expected `<source>-to-<destination> map:`");
+    let name = line.text.strip_suffix(" map:").ok_or_else(header_format)?;
+    let (source, destination) = name.split_once("-to-").ok_or_else(header_format)?;
+    if source != expected_source {
+        return Err(line.error(source, format!("expected a map from `{expected_source}`, found `{source}`")));
     }
-    mapped_num
+    Ok(CategoryMap { source, destination, map: RangeMap::new() })
 }
 
-fn map_ranges(num_ranges: Vec<(u64, u64)>, maps: &Vec<(u64, u64, u64)>) -> Vec<(u64, u64)> {
-    let mut mapped_ranges: Vec<(u64, u64)> = Vec::new();
-    let mut maps = maps.to_owned();
-    maps.sort_by(|a, b| a.1.cmp(&b.1));
-    for num_range in num_ranges {
-        let (mut num_start, num_range) = num_range;
-        let num_end = num_start + num_range;
-        for map in maps.iter() {
-
-            let (dest_start, source_start, map_range) = map;
-            let source_end = source_start + map_range;
-            if (*source_start > num_end) || (num_start > source_end) {
-                continue;
-            }
+pub const TITLE: &str = "If You Give A Seed A Fertilizer";
 
-            let intersection_start = num_start.max(*source_start);
-            let intersection_end = num_end.min(source_end);
-            let intersection_range = intersection_end - intersection_start;
-            let intersection_offset = intersection_start - source_start;
-            mapped_ranges.push((dest_start + intersection_offset, intersection_range));
+pub struct Day;
 
-            if num_start < intersection_start {
-                mapped_ranges.push((num_start, intersection_start - num_start));
-            }
+impl Problem for Day {
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let almanac = Almanac::parse(input)?;
 
-            num_start = intersection_end;
+        let locations = almanac.seeds.iter().map(|&seed| almanac.location(seed));
+        let min_location = locations.min().ok_or(Error::no_solution("no seeds"))?;
 
-        }
+        Ok(min_location.into())
+    }
 
-        if num_start < num_end {
-            mapped_ranges.push((num_start, num_end - num_start));
-        }
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let almanac = Almanac::parse(input)?;
+
+        let locations = almanac.locations(&almanac.seed_ranges()?);
+        let min_location = locations.min().ok_or(Error::no_solution("no seeds"))?;
+
+        Ok(min_location.into())
+    }
 
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(Almanac::parse(input).map(|_| ()))
     }
-    mapped_ranges
 }
 
 #[cfg(test)]
@@ -183,83 +133,43 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let input = "
+    const ALMANAC: &str = "
         seeds: 79 14 55 13
 
         seed-to-soil map:
         50 98 2
         52 50 48
 
-        soil-to-fertilizer map:
+        soil-to-location map:
         0 15 37
-        37 52 2
-        39 0 15
-
-        fertilizer-to-water map:
-        49 53 8
-        0 11 42
-        42 0 7
-        57 7 4
-
-        water-to-light map:
-        88 18 7
-        18 25 70
-
-        light-to-temperature map:
-        45 77 23
-        81 45 19
-        68 64 13
-
-        temperature-to-humidity map:
-        0 69 1
-        1 0 69
-
-        humidity-to-location map:
-        60 56 37
-        56 93 4";
-        let value = Day.part_one(input);
-        assert_eq!(value, "35");
-    }
+        37 52 2";
 
     #[test]
-    fn test_part_two() {
-        let input = "
-        seeds: 79 14 55 13
-
-        seed-to-soil map:
-        50 98 2
-        52 50 48
+    fn test_almanac() {
+        let almanac = Almanac::parse(ALMANAC).unwrap();
+        assert_eq!(almanac.maps.len(), 2);
+        assert_eq!(almanac.maps[1].destination, "location");
+        assert_eq!([79, 14, 55, 98].map(|seed| almanac.location(seed)), [81, 14, 57, 35]);
+
+        let to_soil = almanac.map_between("seed", "soil").unwrap();
+        assert_eq!(to_soil.get(98), 50);
+        assert!(almanac.map_between("soil", "seed").is_none());
+
+        let ranges = almanac.seed_ranges().unwrap();
+        assert_eq!(almanac.locations(&ranges).intervals(), [Interval::new(57, 70), Interval::new(81, 95)]);
+    }
 
-        soil-to-fertilizer map:
-        0 15 37
-        37 52 2
-        39 0 15
-
-        fertilizer-to-water map:
-        49 53 8
-        0 11 42
-        42 0 7
-        57 7 4
-
-        water-to-light map:
-        88 18 7
-        18 25 70
-
-        light-to-temperature map:
-        45 77 23
-        81 45 19
-        68 64 13
-
-        temperature-to-humidity map:
-        0 69 1
-        1 0 69
-
-        humidity-to-location map:
-        60 56 37
-        56 93 4";
-        let value = Day.part_two(input);
-        assert_eq!(value, "46");
+    #[test]
+    fn test_seeds_for() {
+        let almanac = Almanac::parse(ALMANAC).unwrap();
+        // Seed 98 goes through soil 50 to location 35, and no other seed ends up there
+        let seeds = almanac.seeds_for(&IntervalSet::from(Interval::new(35, 36)));
+        assert_eq!(seeds.intervals(), [Interval::new(98, 99)]);
+        let seeds = almanac.seeds_for(&IntervalSet::from(Interval::new(0, 100)));
+        assert_eq!(seeds.len(), 100);
+        assert!(!seeds.contains(100));
+
+        let err = Almanac::parse("seeds: 1 2\n\nsoil-to-water map:\n1 2 3").unwrap_err();
+        assert_eq!(err, Error::parse(3, 1, "expected a map from `seed`, found `soil`"));
     }
 }
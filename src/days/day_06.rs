@@ -1,28 +1,21 @@
 use std::iter::zip;
 
-use aoc2023::filter_input_lines;
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
 
+pub const TITLE: &str = "Wait For It";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let input_lines = filter_input_lines(input);
-        let mut parsed: Vec<Vec<u32>> = input_lines
-            .iter()
-            .map(|line| {
-                line.split_once(':')
-                    .unwrap()
-                    .1
-                    .trim()
-                    .split_ascii_whitespace()
-                    .map(|s| s.parse().unwrap())
-                    .collect()
-            })
-            .collect();
-        let distances = parsed.pop().unwrap();
-        let times = parsed.pop().unwrap();
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let (time_line, distance_line) = time_distance_lines(input)?;
+        let times: Vec<u32> = time_line.parse_words(time_line.split_once(time_line.text, ":")?.1, "a time")?;
+        let distances: Vec<u32> = distance_line.parse_words(distance_line.split_once(distance_line.text, ":")?.1, "a distance")?;
+        if times.len() != distances.len() {
+            return Err(distance_line.error(distance_line.text, format!("expected {} distances", times.len())));
+        }
 
         let mut number_of_wins: Vec<u32> = Vec::new();
         for (max_time, max_distance) in zip(times, distances) {
@@ -37,25 +30,13 @@ impl Problem for Day {
 
         let score: u32 = number_of_wins.iter().product();
 
-        println!("{score}");
-        format!("{score}")
+        Ok(score.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let input_lines = filter_input_lines(input);
-        let mut parsed: Vec<u64> = input_lines
-            .iter()
-            .map(|line| {
-                line.split_once(':')
-                    .unwrap()
-                    .1
-                    .trim()
-                    .replace(" ", "")
-                    .parse().unwrap()
-            })
-            .collect();
-        let max_distance = parsed.pop().unwrap();
-        let max_time = parsed.pop().unwrap();
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let (time_line, distance_line) = time_distance_lines(input)?;
+        let max_time = parse_kerned(&time_line, "a time")?;
+        let max_distance = parse_kerned(&distance_line, "a distance")?;
 
         let temp = ((max_time as f64).powf(2.0) - 4.0 * max_distance as f64).sqrt();
         let min_hold_time = (0.5 * (max_time as f64) - 0.5 * temp).ceil() as u64;
@@ -63,31 +44,20 @@ impl Problem for Day {
         let max_hold_time = max_hold_time.min(max_time);
         let number_of_wins = max_hold_time - min_hold_time + 1;
 
-        println!("{number_of_wins}");
-        format!("{number_of_wins}")
+        Ok(number_of_wins.into())
     }
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "
-            Time:      7  15   30
-            Distance:  9  40  200";
-        let value = Day.part_one(input);
-        assert_eq!(value, "288");
+fn time_distance_lines(input: &str) -> Result<(Line<'_>, Line<'_>), Error> {
+    match input_lines(input)[..] {
+        [time_line, distance_line] => Ok((time_line, distance_line)),
+        [_] | [] => Err(Error::end_of_input(input, "expected a time line and a distance line")),
+        [_, _, extra, ..] => Err(extra.error(extra.text, "unexpected line after the distances")),
     }
+}
 
-    #[test]
-    fn test_part_two() {
-        let input = "
-            Time:      7  15   30
-            Distance:  9  40  200";
-        let value = Day.part_two(input);
-        assert_eq!(value, "71503");
-    }
+/// Parses the numbers after the colon as a single number, ignoring the spaces between digits.
+fn parse_kerned(line: &Line, what: &str) -> Result<u64, Error> {
+    let (_, digits) = line.split_once(line.text, ":")?;
+    line.parse(&digits.replace(' ', ""), what)
 }
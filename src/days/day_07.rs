@@ -1,7 +1,7 @@
 use itertools::Itertools;
-use std::{cmp::Ordering, str::FromStr};
+use std::cmp::Ordering;
 
-use aoc2023::filter_input_lines;
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
 
@@ -110,23 +110,29 @@ impl Hand {
     }
 }
 
-impl FromStr for Hand {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (cards, bid) = s.split_once(' ').unwrap();
+impl Hand {
+    fn parse(line: &Line) -> Result<Self, Error> {
+        let (cards, bid) = line.split_once(line.text, " ")?;
+        if let Some(index) = cards.find(|c| !CARD_ORDERING_PART1.contains(c)) {
+            return Err(line.error_at(index, format!("invalid card `{}`", &cards[index..].chars().next().unwrap())));
+        }
+        if cards.len() != 5 {
+            return Err(line.error(cards, format!("expected 5 cards, found {}", cards.len())));
+        }
         Ok(Hand {
             cards: cards.to_owned(),
-            bid: bid.parse().unwrap(),
+            bid: line.parse(bid.trim(), "a bid")?,
         })
     }
 }
 
+pub const TITLE: &str = "Camel Cards";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-        let mut hands: Vec<Hand> = input.iter().map(|s| s.parse().unwrap()).collect();
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let mut hands: Vec<Hand> = input_lines(input).iter().map(Hand::parse).collect::<Result<_, _>>()?;
 
         hands.sort_by(|a, b| match a.hand_type_part1().cmp(&b.hand_type_part1()) {
             Ordering::Greater => Ordering::Greater,
@@ -140,18 +146,16 @@ impl Problem for Day {
                     let other_card_rank = CARD_ORDERING_PART1.find(other_card).unwrap();
                     return self_card_rank.cmp(&other_card_rank);
                 }
-                return Ordering::Equal;
+                Ordering::Equal
             }
         });
         let winnings: u32 = hands.iter().enumerate().map(|(ind, hand)| (ind as u32 + 1) * hand.bid).sum();
 
-        println!("{winnings}");
-        format!("{winnings}")
+        Ok(winnings.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-        let mut hands: Vec<Hand> = input.iter().map(|s| s.parse().unwrap()).collect();
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let mut hands: Vec<Hand> = input_lines(input).iter().map(Hand::parse).collect::<Result<_, _>>()?;
 
         hands.sort_by(|a, b| match a.hand_type_part2().cmp(&b.hand_type_part2()) {
             Ordering::Greater => Ordering::Greater,
@@ -165,42 +169,15 @@ impl Problem for Day {
                     let other_card_rank = CARD_ORDERING_PART2.find(other_card).unwrap();
                     return self_card_rank.cmp(&other_card_rank);
                 }
-                return Ordering::Equal;
+                Ordering::Equal
             }
         });
         let winnings: u32 = hands.iter().enumerate().map(|(ind, hand)| (ind as u32 + 1) * hand.bid).sum();
 
-        println!("{winnings}");
-        format!("{winnings}")
-    }
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "
-            32T3K 765
-            T55J5 684
-            KK677 28
-            KTJJT 220
-            QQQJA 483";
-        let value = Day.part_one(input);
-        assert_eq!(value, "6440");
+        Ok(winnings.into())
     }
 
-    #[test]
-    fn test_part_two() {
-        let input = "
-            32T3K 765
-            T55J5 684
-            KK677 28
-            KTJJT 220
-            QQQJA 483";
-        let value = Day.part_two(input);
-        assert_eq!(value, "5905");
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(input_lines(input).iter().try_for_each(|line| Hand::parse(line).map(|_| ())))
     }
 }
@@ -1,42 +1,36 @@
 use std::collections::HashMap;
 
-use aoc2023::filter_input_lines;
+use itertools::Itertools;
+
+use crate::cycle::{find_cycle, Cycle, History};
+use crate::math::crt;
+use crate::{input_lines, Answer, Error};
 
 use super::Problem;
 
-const MAX_STEPS: u32 = 1_000_000;
-// const MAX_STEPS: u32 = 20;
+pub const TITLE: &str = "Haunted Wasteland";
 
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-        let instructions = input.iter().next().unwrap().chars().cycle();
-
-        let mut map = HashMap::new();
-        for line in input.iter().skip(1) {
-            let (origin, destinations) = line.split_once('=').unwrap();
-            let (dest_left, dest_right) = destinations.trim().split_once(',').unwrap();
-            let origin = origin.trim();
-            let dest_left = &dest_left[1..4];
-            let dest_right = &dest_right[1..4];
-            map.insert(origin, (dest_left, dest_right));
-        }
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let (instructions, map) = parse_network(input)?;
+        // Past one step per (node, instruction index) state, some state has repeated and the walk is looping
+        let max_steps = map.len() * instructions.len();
+        let instructions = instructions.chars().cycle();
 
         let mut num_steps = 0;
         let mut location = "AAA";
         for instruction in instructions {
+            let (dest_left, dest_right) = map.get(location).ok_or(Error::no_solution(format!("no node `{location}`")))?;
             num_steps += 1;
-            if num_steps >= MAX_STEPS {
-                break;
+            if num_steps > max_steps {
+                return Err(Error::no_solution("ZZZ is not reachable from AAA"));
             }
-
-            let (dest_left, dest_right) = map.get(location).unwrap();
             match instruction {
                 'L' => location = dest_left,
                 'R' => location = dest_right,
-                _ => panic!(),
+                _ => unreachable!(),
             }
 
             if location == "ZZZ" {
@@ -44,71 +38,86 @@ impl Problem for Day {
             }
         }
 
-        println!("{num_steps}");
-        format!("{num_steps}")
+        Ok(num_steps.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-        let instructions = input.iter().next().unwrap();
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let (instructions, map) = parse_network(input)?;
         let num_instructions = instructions.len();
+        let start_locations: Vec<&str> = map.keys().copied().filter(|origin| origin.ends_with('A')).collect();
 
-        let mut map = HashMap::new();
-        let mut start_locations = Vec::new();
-        for line in input.iter().skip(1) {
-            let (origin, destinations) = line.split_once('=').unwrap();
-            let (dest_left, dest_right) = destinations.trim().split_once(',').unwrap();
-            let origin = origin.trim();
-            let dest_left = &dest_left[1..4];
-            let dest_right = &dest_right[1..4];
-            if origin.chars().nth(2).unwrap() == 'A' {
-                start_locations.push(origin);
-            }
-            map.insert(origin, (dest_left, dest_right));
+        if let Some(location) = map.values().flat_map(|&(left, right)| [left, right]).find(|dest| !map.contains_key(dest)) {
+            return Err(Error::no_solution(format!("no node `{location}`")));
         }
 
-        let mut cycles = Vec::new();
-        for start_location in start_locations {
-            let mut num_steps = 0;
-            let mut visited = Vec::new();
-            let mut location = start_location;
-            visited.push(location);
-
-            'outer: for instruction in instructions.chars().cycle() {
-                num_steps += 1;
-                if num_steps >= MAX_STEPS {
-                    break;
-                }
-
-                let (dest_left, dest_right) = map.get(location).unwrap();
-                match instruction {
-                    'L' => location = dest_left,
-                    'R' => location = dest_right,
-                    _ => panic!(),
-                }
-
-                visited.push(location);
-
-                let mut pos: i32 = num_steps as i32 - num_instructions as i32;
-                while pos >= 0 {
-                    if visited[pos as usize] == location {
-                        cycles.push(num_steps as u64 - pos as u64);
-                        break 'outer;
-                    }
-                    pos -= num_instructions as i32;
-                }
-            }
+        // The state is the location and the position in the instructions, the ghost loops once it repeats
+        let instructions = instructions.as_bytes();
+        let step = |&(location, index): &(&str, usize)| {
+            let (dest_left, dest_right) = map[location];
+            let location = if instructions[index] == b'L' { dest_left } else { dest_right };
+            (location, (index + 1) % num_instructions)
+        };
+        let histories: Vec<History<(&str, usize)>> =
+            start_locations.into_iter().map(|start_location| find_cycle((start_location, 0), step)).collect();
+        if histories.is_empty() {
+            return Err(Error::no_solution("no nodes ending in `A`"));
         }
+        let at_end = |history: &History<(&str, usize)>, num_steps: usize| history.state_at(num_steps).0.ends_with('Z');
 
-        let mut align_step = num_instructions as u64;
-        for cycle_len in cycles {
-            let multiple = cycle_len / num_instructions as u64;
-            align_step *= multiple as u64;
+        // Until all the ghosts are looping, just check every step
+        let loop_start = histories.iter().map(|history| history.cycle.start).max().unwrap_or(0);
+        if let Some(num_steps) = (1..loop_start).find(|&num_steps| histories.iter().all(|history| at_end(history, num_steps))) {
+            return Ok(num_steps.into());
         }
 
-        println!("{align_step}");
-        format!("{align_step}")
+        // After that every ghost is at an end node on the steps with certain remainders modulo its cycle length,
+        // so try every combination of them
+        let end_congruences: Vec<Vec<(i64, i64)>> = histories
+            .iter()
+            .map(|history| {
+                let Cycle { start, length } = history.cycle;
+                (start..start + length)
+                    .filter(|&num_steps| at_end(history, num_steps))
+                    .map(|num_steps| (num_steps as i64, length as i64))
+                    .collect()
+            })
+            .collect();
+        let first_step = loop_start.max(1) as i64;
+        let num_steps = end_congruences
+            .iter()
+            .multi_cartesian_product()
+            .filter_map(|congruences| crt(congruences.into_iter().copied()))
+            .map(|(remainder, modulus)| first_step + (remainder - first_step).rem_euclid(modulus))
+            .min()
+            .ok_or(Error::no_solution("the ghosts are never at the end nodes at the same time"))?;
+
+        Ok(num_steps.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(parse_network(input).map(|_| ()))
+    }
+}
+
+type Network<'a> = HashMap<&'a str, (&'a str, &'a str)>;
+
+fn parse_network(input: &str) -> Result<(&str, Network<'_>), Error> {
+    let lines = input_lines(input);
+    let instructions = lines.first().ok_or(Error::end_of_input(input, "expected the instructions"))?;
+    if let Some(index) = instructions.text.find(|c| c != 'L' && c != 'R') {
+        return Err(instructions.error_at(index, "expected `L` or `R`"));
+    }
+
+    let mut map = HashMap::new();
+    for line in lines.iter().skip(1) {
+        let (origin, destinations) = line.name_tuple(line.text)?;
+        let [dest_left, dest_right] = destinations[..] else {
+            return Err(line.error(line.text, "expected `<node> = (<left>, <right>)`"));
+        };
+        map.insert(origin, (dest_left, dest_right));
     }
+
+    Ok((instructions.text, map))
 }
 
 #[cfg(test)]
@@ -117,31 +126,12 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part_one() {
-        let input = "
-        LLR
-
-        AAA = (BBB, BBB)
-        BBB = (AAA, ZZZ)
-        ZZZ = (ZZZ, ZZZ)";
-        let value = Day.part_one(input);
-        assert_eq!(value, "6");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        LR
-
-        11A = (11B, XXX)
-        11B = (XXX, 11Z)
-        11Z = (11B, XXX)
-        22A = (22B, XXX)
-        22B = (22C, 22C)
-        22C = (22Z, 22Z)
-        22Z = (22B, 22B)
-        XXX = (XXX, XXX)";
-        let value = Day.part_two(input);
-        assert_eq!(value, "6");
+    fn test_unreachable_end() {
+        let input = "LR\n\nAAA = (AAA, AAA)";
+        assert_eq!(Day.part_one(input), Err(Error::no_solution("ZZZ is not reachable from AAA")));
+        let input = "LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(Day.part_one(input), Err(Error::no_solution("ZZZ is not reachable from AAA")));
+        let input = "LLR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(Day.part_one(input), Ok(3.into()));
     }
 }
@@ -1,22 +1,16 @@
-use aoc2023::filter_input_lines;
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
 
+pub const TITLE: &str = "Mirage Maintenance";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
         let mut total_value = 0;
-        for line in input {
-            let mut values: Vec<i32> = line.split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-            let mut differences = Vec::new();
-            differences.push(values.clone());
-            while !values.iter().all(|v| *v == 0) {
-                values = values.windows(2).map(|s| s[1] - s[0]).collect();
-                differences.push(values.clone());
-            }
+        for line in input_lines(input) {
+            let differences = difference_rows(&line)?;
 
             let mut value = 0;
             for values in differences.iter().rev() {
@@ -26,22 +20,13 @@ impl Problem for Day {
             total_value += value;
         }
 
-        println!("{total_value}");
-        format!("{total_value}")
+        Ok(total_value.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let input = filter_input_lines(input);
-
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
         let mut total_value = 0;
-        for line in input {
-            let mut values: Vec<i32> = line.split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-            let mut differences = Vec::new();
-            differences.push(values.clone());
-            while !values.iter().all(|v| *v == 0) {
-                values = values.windows(2).map(|s| s[1] - s[0]).collect();
-                differences.push(values.clone());
-            }
+        for line in input_lines(input) {
+            let differences = difference_rows(&line)?;
 
             let mut value = 0;
             for values in differences.iter().rev() {
@@ -51,9 +36,23 @@ impl Problem for Day {
             total_value += value;
         }
 
-        println!("{total_value}");
-        format!("{total_value}")
+        Ok(total_value.into())
+    }
+}
+
+/// The values on the line and their differences, row by row down to a row of all zeroes.
+fn difference_rows(line: &Line) -> Result<Vec<Vec<i32>>, Error> {
+    let mut values: Vec<i32> = line.parse_words(line.text, "a number")?;
+    let mut differences = Vec::new();
+    differences.push(values.clone());
+    while !values.is_empty() && !values.iter().all(|v| *v == 0) {
+        values = values.windows(2).map(|s| s[1] - s[0]).collect();
+        differences.push(values.clone());
+    }
+    if values.is_empty() {
+        return Err(line.error(line.text, "sequence never reaches all zeroes"));
     }
+    Ok(differences)
 }
 
 #[cfg(test)]
@@ -62,22 +61,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part_one() {
-        let input = "
-        0 3 6 9 12 15
-        1 3 6 10 15 21
-        10 13 16 21 30 45";
-        let value = Day.part_one(input);
-        assert_eq!(value, "114");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        0 3 6 9 12 15
-        1 3 6 10 15 21
-        10 13 16 21 30 45";
-        let value = Day.part_two(input);
-        assert_eq!(value, "2");
+    fn test_short_sequence() {
+        assert_eq!(Day.part_one("1 5"), Err(Error::parse(1, 1, "sequence never reaches all zeroes")));
+        assert_eq!(Day.part_two("  1 5"), Err(Error::parse(1, 3, "sequence never reaches all zeroes")));
+        assert_eq!(Day.part_one("1 3 5"), Ok(7.into()));
     }
 }
@@ -1,27 +1,21 @@
-use aoc2023::filter_input_lines;
+use crate::geom::Point2;
+use crate::grid::{Grid, Pos};
+use crate::{Answer, Error};
 
 use super::Problem;
 
 // const EXPANSION_FACTOR: usize = 10;
 const EXPANSION_FACTOR: usize = 1_000_000;
 
+type Galaxies = Vec<Pos>;
+
+pub const TITLE: &str = "Cosmic Expansion";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let lines = filter_input_lines(input);
-
-        let mut galaxy_coordinates = Vec::new();
-        for (i, line) in lines.iter().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                if c == '#' {
-                    galaxy_coordinates.push((i, j));
-                }
-            }
-        }
-
-        let height = lines.len();
-        let width = lines[0].len();
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let (mut galaxy_coordinates, height, width) = find_galaxies(input)?;
 
         let empty_rows: Vec<usize> = (0..height)
             .filter(|row| galaxy_coordinates.iter().all(|coord| coord.0 != *row))
@@ -48,31 +42,18 @@ impl Problem for Day {
             }
         }
 
-        let distance_sum: usize = galaxy_coordinates.iter().map(|coord1| {
-            galaxy_coordinates.iter().map(|coord2| {
-                coord1.0.abs_diff(coord2.0) + coord1.1.abs_diff(coord2.1)
-            }).sum::<usize>()
+        let distance_sum: i64 = galaxy_coordinates.iter().map(|&coord1| {
+            galaxy_coordinates.iter().map(|&coord2| {
+                Point2::from(coord1).manhattan(Point2::from(coord2))
+            }).sum::<i64>()
         }).sum();
         let distance_sum = distance_sum / 2;
 
-        println!("{distance_sum}");
-        format!("{distance_sum}")
+        Ok(distance_sum.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let lines = filter_input_lines(input);
-
-        let mut galaxy_coordinates = Vec::new();
-        for (i, line) in lines.iter().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                if c == '#' {
-                    galaxy_coordinates.push((i, j));
-                }
-            }
-        }
-
-        let height = lines.len();
-        let width = lines[0].len();
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let (galaxy_coordinates, height, width) = find_galaxies(input)?;
 
         let empty_rows: Vec<usize> = (0..height)
             .filter(|row| galaxy_coordinates.iter().all(|coord| coord.0 != *row))
@@ -92,47 +73,21 @@ impl Problem for Day {
         }).sum();
         let distance_sum = distance_sum / 2;
 
-        println!("{distance_sum}");
-        format!("{distance_sum}")
+        Ok(distance_sum.into())
     }
-}
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "
-        ...#......
-        .......#..
-        #.........
-        ..........
-        ......#...
-        .#........
-        .........#
-        ..........
-        .......#..
-        #...#.....";
-        let value = Day.part_one(input);
-        assert_eq!(value, "374");
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(find_galaxies(input).map(|_| ()))
     }
+}
 
-    // #[test]
-    // fn test_part_two() {
-    //     let input = "
-    //     ...#......
-    //     .......#..
-    //     #.........
-    //     ..........
-    //     ......#...
-    //     .#........
-    //     .........#
-    //     ..........
-    //     .......#..
-    //     #...#.....";
-    //     let value = Day.part_two(input);
-    //     assert_eq!(value, "1030");
-    // }
+/// Returns the galaxy coordinates and the height and width of the image.
+fn find_galaxies(input: &str) -> Result<(Galaxies, usize, usize), Error> {
+    let image = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+    let galaxy_coordinates = image.iter().filter(|(_, &galaxy)| galaxy).map(|(pos, _)| pos).collect();
+    Ok((galaxy_coordinates, image.height(), image.width()))
 }
@@ -1,54 +1,61 @@
 use std::collections::HashMap;
 
-use aoc2023::filter_input_lines;
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
 
+pub const TITLE: &str = "Hot Springs";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let lines = filter_input_lines(input);
-
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
         let mut num_arrangements = 0;
-        for line in lines {
-            let (records, counts) = line.split_once(' ').unwrap();
-            let target_counts: Vec<usize> = counts.split(',').map(|s| s.parse().unwrap()).collect();
+        for line in input_lines(input) {
+            let (records, target_counts) = parse_row(&line)?;
             num_arrangements += find_num_arrangements(records, target_counts, &mut HashMap::new());
         }
 
-        println!("{num_arrangements}");
-        format!("{num_arrangements}")
+        Ok(num_arrangements.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let lines = filter_input_lines(input);
-
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
         let mut num_arrangements = 0;
-        for line in lines {
-            let (records, counts) = line.split_once(' ').unwrap();
-            let target_counts: Vec<usize> = counts.split(',').map(|s| s.parse().unwrap()).collect();
+        for line in input_lines(input) {
+            let (records, target_counts) = parse_row(&line)?;
 
-            let records = vec![records; 5].join("?");
+            let records = [records; 5].join("?");
             let target_counts: Vec<usize> = vec![target_counts; 5].concat().into_iter().collect();
 
             num_arrangements += find_num_arrangements(&records, target_counts, &mut HashMap::new());
         }
 
-        println!("{num_arrangements}");
-        format!("{num_arrangements}")
+        Ok(num_arrangements.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(input_lines(input).iter().try_for_each(|line| parse_row(line).map(|_| ())))
     }
 }
 
+fn parse_row<'a>(line: &Line<'a>) -> Result<(&'a str, Vec<usize>), Error> {
+    let (records, counts) = line.split_once(line.text, " ")?;
+    if let Some(index) = records.find(|c| !".#?".contains(c)) {
+        return Err(line.error_at(index, "expected `.`, `#` or `?`"));
+    }
+    let target_counts = line.parse_separated(counts.trim(), ',', "a group size")?;
+    Ok((records, target_counts))
+}
+
 fn find_num_arrangements(records: &str, target_counts: Vec<usize>, counts: &mut HashMap<(String, Vec<usize>), usize>) -> usize {
-    if records.len() == 0 {
-        if target_counts.len() == 0 {
+    if records.is_empty() {
+        if target_counts.is_empty() {
             return 1;
         } else {
             return 0;
         }
     }
-    if target_counts.len() == 0 {
+    if target_counts.is_empty() {
         if records.contains('#') {
             return 0;
         } else {
@@ -63,7 +70,7 @@ fn find_num_arrangements(records: &str, target_counts: Vec<usize>, counts: &mut
 
     let mut count = 0;
 
-    let next_record = records.chars().nth(0).unwrap();
+    let next_record = records.chars().next().unwrap();
     if next_record == '.' || next_record == '?' {
         count += find_num_arrangements(&records[1..], target_counts.clone(), counts);
     }
@@ -84,35 +91,3 @@ fn find_num_arrangements(records: &str, target_counts: Vec<usize>, counts: &mut
 
     count
 }
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "
-        ???.### 1,1,3
-        .??..??...?##. 1,1,3
-        ?#?#?#?#?#?#?#? 1,3,1,6
-        ????.#...#... 4,1,1
-        ????.######..#####. 1,6,5
-        ?###???????? 3,2,1";
-        let value = Day.part_one(input);
-        assert_eq!(value, "21");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        ???.### 1,1,3
-        .??..??...?##. 1,1,3
-        ?#?#?#?#?#?#?#? 1,3,1,6
-        ????.#...#... 4,1,1
-        ????.######..#####. 1,6,5
-        ?###???????? 3,2,1";
-        let value = Day.part_two(input);
-        assert_eq!(value, "525152");
-    }
-}
@@ -1,5 +1,6 @@
 
-use regex::Regex;
+use crate::grid::Grid;
+use crate::{input_blocks, Answer, Error};
 
 use super::Problem;
 
@@ -9,20 +10,22 @@ enum Mirror {
     Horizontal(usize)
 }
 
+pub const TITLE: &str = "Point of Incidence";
+
 pub struct Day;
 
 impl Problem for Day {
 
-    fn part_one(&self, input: &str) -> String {
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
 
-        let separator = Regex::new(r"\n[ ]*\n").unwrap();
-        let patterns: Vec<Vec<&str>> = separator.split(input).map(|s| s.split_ascii_whitespace().collect()).collect();
+        let patterns = parse_patterns(input)?;
 
         let mut vertical_mirrors = Vec::new();
         let mut horizontal_mirrors = Vec::new();
 
         for pattern in patterns {
-            match find_mirrors(&pattern)[0] {
+            let mirror = find_mirrors(&pattern).into_iter().next().ok_or(Error::no_solution("pattern without a mirror"))?;
+            match mirror {
                 Mirror::Horizontal(row) => horizontal_mirrors.push(row),
                 Mirror::Vertical(col) => vertical_mirrors.push(col),
             }
@@ -30,40 +33,30 @@ impl Problem for Day {
 
         let total_number: usize = vertical_mirrors.iter().sum::<usize>() + 100 * horizontal_mirrors.iter().sum::<usize>();
 
-        println!("{total_number}");
-        format!("{total_number}")
+        Ok(total_number.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let separator = Regex::new(r"\n[ ]*\n").unwrap();
-        let patterns: Vec<Vec<&str>> = separator.split(input).map(|s| s.split_ascii_whitespace().collect()).collect();
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let patterns = parse_patterns(input)?;
 
         let mut vertical_mirrors = Vec::new();
         let mut horizontal_mirrors = Vec::new();
 
         'outer: for pattern in patterns {
 
-            let original_mirror = find_mirrors(&pattern).pop().unwrap();
-
-            for i in 0..pattern.len() {
-                for j in 0..pattern[0].len() {
-                    let mut new_pattern = pattern.clone();
-                    let mut new_line = pattern[i].to_owned();
-                    match new_line.chars().nth(j).unwrap() {
-                        '#' => new_line.replace_range(j..(j+1), "."),
-                        '.' => new_line.replace_range(j..(j+1), "#"),
-                        _ => panic!()
-                    }
-                    new_pattern[i] = &new_line;
-                    let new_mirrors = find_mirrors(&new_pattern);
-                    for mirror in new_mirrors {
-                        if mirror != original_mirror {
-                            match mirror {
-                                Mirror::Horizontal(row) => horizontal_mirrors.push(row),
-                                Mirror::Vertical(col) => vertical_mirrors.push(col),
-                            }
-                            continue 'outer;
+            let original_mirror = find_mirrors(&pattern).pop().ok_or(Error::no_solution("pattern without a mirror"))?;
+
+            for pos in pattern.positions() {
+                let mut new_pattern = pattern.clone();
+                new_pattern[pos] = if pattern[pos] == '#' { '.' } else { '#' };
+                let new_mirrors = find_mirrors(&new_pattern);
+                for mirror in new_mirrors {
+                    if mirror != original_mirror {
+                        match mirror {
+                            Mirror::Horizontal(row) => horizontal_mirrors.push(row),
+                            Mirror::Vertical(col) => vertical_mirrors.push(col),
                         }
+                        continue 'outer;
                     }
                 }
             }
@@ -72,91 +65,37 @@ impl Problem for Day {
 
         let total_number: usize = vertical_mirrors.iter().sum::<usize>() + 100 * horizontal_mirrors.iter().sum::<usize>();
 
-        println!("{total_number}");
-        format!("{total_number}")
+        Ok(total_number.into())
     }
 
-}
-
-
-fn find_mirrors(pattern: &Vec<&str>) -> Vec<Mirror> {
-
-    let mut mirrors = Vec::new();
-
-    // Check vertical mirror
-    for split_col in 1..(pattern[0].len()) {
-        let is_mirrored = pattern.iter().all(|line| {
-            let (left, right) = line.split_at(split_col);
-            left.chars().rev().zip(right.chars()).all(|(l, r)| l == r)
-        });
-        if is_mirrored {
-            mirrors.push(Mirror::Vertical(split_col));
-        }
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(parse_patterns(input).map(|_| ()))
     }
 
-    // Check horizontal mirror
-    for split_row in 1..(pattern.len()) {
-        let is_mirrored = (0..pattern[0].len()).all(|col| {
-            let col_chars: String = pattern.iter().map(|line| line.chars().nth(col).unwrap()).collect();
-            let (top, bottom) = col_chars.split_at(split_row);
-            top.chars().rev().zip(bottom.chars()).all(|(l, r)| l == r)
-        });
-        if is_mirrored {
-            mirrors.push(Mirror::Horizontal(split_row));
-        }
-    }
+}
 
-    mirrors
 
+fn parse_patterns(input: &str) -> Result<Vec<Grid<char>>, Error> {
+    input_blocks(input)
+        .iter()
+        .map(|block| Grid::from_lines(block, |c| "#.".contains(c).then_some(c)))
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "
-        #.##..##.
-        ..#.##.#.
-        ##......#
-        ##......#
-        ..#.##.#.
-        ..##..##.
-        #.#.##.#.
-        
-        #...##..#
-        #....#..#
-        ..##..###
-        #####.##.
-        #####.##.
-        ..##..###
-        #....#..#";
-        let value = Day.part_one(input);
-        assert_eq!(value, "405");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        #.##..##.
-        ..#.##.#.
-        ##......#
-        ##......#
-        ..#.##.#.
-        ..##..##.
-        #.#.##.#.
-        
-        #...##..#
-        #....#..#
-        ..##..###
-        #####.##.
-        #####.##.
-        ..##..###
-        #....#..#";
-        let value = Day.part_two(input);
-        assert_eq!(value, "400");
-    }
+fn find_mirrors(pattern: &Grid<char>) -> Vec<Mirror> {
+    let mut mirrors: Vec<Mirror> = find_mirror_cols(pattern).into_iter().map(Mirror::Vertical).collect();
+    mirrors.extend(find_mirror_cols(&pattern.transposed()).into_iter().map(Mirror::Horizontal));
+    mirrors
+}
 
+/// Columns with a vertical mirror on their left side.
+fn find_mirror_cols(pattern: &Grid<char>) -> Vec<usize> {
+    (1..pattern.width())
+        .filter(|&split_col| {
+            pattern.rows().all(|row| {
+                let (left, right) = row.split_at(split_col);
+                left.iter().rev().zip(right).all(|(l, r)| l == r)
+            })
+        })
+        .collect()
 }
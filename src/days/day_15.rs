@@ -1,30 +1,36 @@
+use crate::{input_lines, Answer, Error};
+
 use super::Problem;
 
+pub const TITLE: &str = "Lens Library";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let hash_sum: usize = input.trim().split(',').map(|s| get_hash(s)).sum();
-        println!("{hash_sum}");
-        format!("{hash_sum}")
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let hash_sum: usize = input.trim().split(',').map(get_hash).sum();
+        Ok(hash_sum.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
         let mut boxes: Vec<Vec<(&str, u32)>> = (0..256).map(|_| Vec::new()).collect();
-        for instruction in input.trim().split(',') {
-            let op_ind = instruction.chars().position(|c| c == '-' || c == '=').unwrap();
+        let lines = input_lines(input);
+        for (line, instruction) in lines.iter().flat_map(|line| line.text.split(',').map(move |s| (line, s))) {
+            let op_ind = instruction
+                .find(['-', '='])
+                .ok_or(line.error(instruction, format!("expected `-` or `=` in `{instruction}`")))?;
             let label = &instruction[..op_ind];
             let hash = get_hash(label);
-            let operation = instruction.chars().nth(op_ind).unwrap();
+            let operation = &instruction[op_ind..op_ind + 1];
             match operation {
-                '-' => {
+                "-" => {
                     let bx = &mut boxes[hash];
                     if let Some(ind) = bx.iter().position(|(l, _)| *l == label) {
                         bx.remove(ind);
                     }
                 }
-                '=' => {
-                    let focal_length: u32 = instruction[(op_ind + 1)..].parse().unwrap();
+                "=" => {
+                    let focal_length: u32 = line.parse(&instruction[(op_ind + 1)..], "a focal length")?;
                     let new_lens = (label, focal_length);
                     let bx = &mut boxes[hash];
                     if let Some(ind) = bx.iter().position(|(l, _)| *l == label) {
@@ -33,7 +39,7 @@ impl Problem for Day {
                         bx.push(new_lens);
                     }
                 }
-                _ => panic!(),
+                _ => unreachable!(),
             }
         }
         let total_focusing_power: u32 = boxes
@@ -47,8 +53,7 @@ impl Problem for Day {
                     .sum::<u32>()
             })
             .sum();
-        println!("{total_focusing_power}");
-        format!("{total_focusing_power}")
+        Ok(total_focusing_power.into())
     }
 }
 
@@ -61,23 +66,3 @@ fn get_hash(s: &str) -> usize {
     }
     hash as usize
 }
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        let value = Day.part_one(input);
-        assert_eq!(value, "1320");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        let value = Day.part_two(input);
-        assert_eq!(value, "145");
-    }
-}
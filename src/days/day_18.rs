@@ -1,114 +1,79 @@
-
-use aoc2023::filter_input_lines;
-use regex::Regex;
+use crate::geom::{Direction4, Point2};
+use crate::polygon::Polygon;
+use crate::{input_lines, Answer, Error, Line};
 
 use super::Problem;
 
+const PLAN_FORMAT: &str = "expected `<direction> <distance> (#<colour>)`";
+
+pub const TITLE: &str = "Lavaduct Lagoon";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let lines = filter_input_lines(input);
-        let re = Regex::new(r"(?<dir>.)\s*(?<amount>\d+)\s*\(#[a-fA-F0-9]{6}\)").unwrap();
-        let mut pos = (0, 0);
-        let mut area = 0;
-        let mut num_edge_squares = 0;
-        for line in lines {
-            let cap = re.captures(line).unwrap();
-            let dir = &cap["dir"];
-            let amount: i32 = cap["amount"].parse().unwrap();
-            let offset = match dir {
-                "R" => (0, amount),
-                "L" => (0, -amount),
-                "U" => (-amount, 0),
-                "D" => (amount, 0),
-                _ => panic!(),
-            };
-            pos = (pos.0 + offset.0, pos.1 + offset.1);
-            area += pos.1 * offset.0;
-            num_edge_squares += amount;
-        }
-
-        area = area.abs() + num_edge_squares / 2 + 1;
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let steps: Vec<(Direction4, i64)> = parse_plan(input)?.into_iter().map(|step| (step.dir, step.amount)).collect();
 
-        println!("{area}");
-        format!("{area}")
+        Ok(lagoon_size(&steps).into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let lines = filter_input_lines(input);
-        let re = Regex::new(r".\s*\d+\s*\(#(?<code>[a-fA-F0-9]{6})\)").unwrap();
-        let mut pos = (0, 0);
-        let mut area: i64 = 0;
-        let mut num_edge_squares = 0;
-        for line in lines {
-            let cap = re.captures(line).unwrap();
-            let code: &str = &cap["code"];
-            let amount = i64::from_str_radix(&code[..5], 16).unwrap();
-            let dir = &code[5..6];
-            let offset = match dir {
-                "0" => (0, amount),
-                "1" => (amount, 0),
-                "2" => (0, -amount),
-                "3" => (-amount, 0),
-                _ => panic!(),
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let mut steps = Vec::new();
+        for PlanStep { line, colour, .. } in parse_plan(input)? {
+            let amount = i64::from_str_radix(&colour[..5], 16).unwrap();
+            let dir = &colour[5..6];
+            let dir = match dir {
+                "0" => Direction4::Right,
+                "1" => Direction4::Down,
+                "2" => Direction4::Left,
+                "3" => Direction4::Up,
+                _ => return Err(line.error(dir, format!("invalid direction code `{dir}`"))),
             };
-            pos = (pos.0 + offset.0, pos.1 + offset.1);
-            area += pos.1 * offset.0;
-            num_edge_squares += amount;
+            steps.push((dir, amount));
         }
 
-        area = area.abs() + num_edge_squares / 2 + 1;
+        Ok(lagoon_size(&steps).into())
+    }
 
-        println!("{area}");
-        format!("{area}")
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(parse_plan(input).map(|_| ()))
     }
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
+struct PlanStep<'a> {
+    line: Line<'a>,
+    dir: Direction4,
+    amount: i64,
+    /// The six hex digits of the colour code.
+    colour: &'a str,
+}
 
-    #[test]
-    fn test_part_one() {
-        let input = "
-        R 6 (#70c710)
-        D 5 (#0dc571)
-        L 2 (#5713f0)
-        D 2 (#d2c081)
-        R 2 (#59c680)
-        D 2 (#411b91)
-        L 5 (#8ceee2)
-        U 2 (#caa173)
-        L 1 (#1b58a2)
-        U 2 (#caa171)
-        R 2 (#7807d2)
-        U 3 (#a77fa3)
-        L 2 (#015232)
-        U 2 (#7a21e3)";
-        let value = Day.part_one(input);
-        assert_eq!(value, "62");
-    }
+fn parse_plan(input: &str) -> Result<Vec<PlanStep<'_>>, Error> {
+    input_lines(input)
+        .into_iter()
+        .map(|line| {
+            let [dir, amount, colour] = line.text.split_whitespace().collect::<Vec<_>>()[..] else {
+                return Err(line.error(line.text, PLAN_FORMAT));
+            };
+            let dir = line.parse(dir, "a direction")?;
+            let amount = line.parse(amount, "a distance")?;
+            let colour = line.delimited(colour, "(#", ")")?;
+            if colour.len() != 6 || !colour.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(line.error(colour, format!("expected six hex digits, found `{colour}`")));
+            }
+            Ok(PlanStep { line, dir, amount, colour })
+        })
+        .collect()
+}
 
-    #[test]
-    fn test_part_two() {
-        let input = "
-        R 6 (#70c710)
-        D 5 (#0dc571)
-        L 2 (#5713f0)
-        D 2 (#d2c081)
-        R 2 (#59c680)
-        D 2 (#411b91)
-        L 5 (#8ceee2)
-        U 2 (#caa173)
-        L 1 (#1b58a2)
-        U 2 (#caa171)
-        R 2 (#7807d2)
-        U 3 (#a77fa3)
-        L 2 (#015232)
-        U 2 (#7a21e3)";
-        let value = Day.part_two(input);
-        assert_eq!(value, "952408144115");
-    }
+/// Number of squares dug out by following the steps and then digging out the inside of the loop.
+fn lagoon_size(steps: &[(Direction4, i64)]) -> i64 {
+    let corners = steps
+        .iter()
+        .scan(Point2::ORIGIN, |pos, &(dir, amount)| {
+            *pos += dir.offset() * amount;
+            Some(*pos)
+        })
+        .collect();
+    Polygon::new(corners).lattice_points()
 }
@@ -1,7 +1,8 @@
 use core::panic;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use regex::Regex;
+use crate::interval::{HyperRect, Interval};
+use crate::{input_blocks, Answer, Error, Line};
 
 use super::Problem;
 
@@ -29,199 +30,183 @@ impl Part {
             _ => panic!(),
         }
     }
-    fn replace(&self, var: char, val: u32) -> Part {
-        let mut new_part = self.clone();
-        match var {
-            'x' => new_part.x = val,
-            'm' => new_part.m = val,
-            'a' => new_part.a = val,
-            's' => new_part.s = val,
-            _ => panic!(),
-        }
-        new_part
-    }
 }
 
+type Workflows = HashMap<String, Vec<Filter>>;
+
+pub const TITLE: &str = "Aplenty";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let (workflows, parts) = get_workflows_parts(input);
-
-        let accepted_parts: Vec<Part> = parts
-            .into_iter()
-            .filter_map(|part| {
-                let mut filters = workflows.get("in").unwrap();
-                loop {
-                    for filter in filters.iter() {
-                        let destination = match filter {
-                            Filter::Destination(dest) => dest,
-                            Filter::Map((var, op, val, dest)) => {
-                                let part_val = part.get_val(*var);
-                                let res = match op {
-                                    '>' => part_val > *val,
-                                    '<' => part_val < *val,
-                                    _ => panic!(),
-                                };
-                                if res {
-                                    dest
-                                } else {
-                                    continue;
-                                }
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let (workflows, parts) = get_workflows_parts(input)?;
+
+        let mut accepted_parts: Vec<Part> = Vec::new();
+        for part in parts {
+            let mut filters = get_workflow(&workflows, "in")?;
+            let mut visited = HashSet::from(["in"]);
+            'workflow: loop {
+                for filter in filters.iter() {
+                    let destination = match filter {
+                        Filter::Destination(dest) => dest,
+                        Filter::Map((var, op, val, dest)) => {
+                            let part_val = part.get_val(*var);
+                            let res = match op {
+                                '>' => part_val > *val,
+                                '<' => part_val < *val,
+                                _ => panic!(),
+                            };
+                            if res {
+                                dest
+                            } else {
+                                continue;
                             }
-                        };
-                        match destination.as_str() {
-                            "A" => return Some(part),
-                            "R" => return None,
-                            dest => {
-                                filters = workflows.get(dest).unwrap();
-                                break;
+                        }
+                    };
+                    match destination.as_str() {
+                        "A" => {
+                            accepted_parts.push(part);
+                            break 'workflow;
+                        }
+                        "R" => break 'workflow,
+                        dest => {
+                            if !visited.insert(dest) {
+                                return Err(workflow_cycle(dest));
                             }
+                            filters = get_workflow(&workflows, dest)?;
+                            continue 'workflow;
                         }
                     }
                 }
-            })
-            .collect();
+                return Err(Error::no_solution("workflow without a final destination"));
+            }
+        }
 
         let total_value: u32 = accepted_parts.iter().map(|part| part.x + part.m + part.a + part.s).sum();
 
-        println!("{total_value}");
-        format!("{total_value}")
+        Ok(total_value.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let (workflows, _) = get_workflows_parts(input);
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let (workflows, _) = get_workflows_parts(input)?;
 
-        let init_range = (
-            Part { x: 1, m: 1, a: 1, s: 1 },
-            Part {
-                x: 4000,
-                m: 4000,
-                a: 4000,
-                s: 4000,
-            },
-            "in",
-        );
-        let mut ranges = vec![init_range];
+        let all_ratings = HyperRect::new([Interval::new(1, 4001); 4]);
+        // Boxes of x, m, a and s ratings, the workflow they are in and the workflows they went through to get there
+        let mut ranges = vec![(all_ratings, "in", vec!["in"])];
         let mut final_ranges = Vec::new();
 
-        while let Some((mut start, mut end, workflow_name)) = ranges.pop() {
-            let filters = workflows.get(workflow_name).unwrap();
+        while let Some((range, workflow_name, path)) = ranges.pop() {
+            let filters = get_workflow(&workflows, workflow_name)?;
+            let mut rest = Some(range);
             for filter in filters {
-                let (destination, new_range) = match filter {
-                    Filter::Destination(dest) => (Some((start, end, dest.as_str())), None),
+                let Some(range) = rest else {
+                    break;
+                };
+                let (matching, dest) = match filter {
+                    Filter::Destination(dest) => {
+                        rest = None;
+                        (Some(range), dest)
+                    }
                     Filter::Map((var, op, val, dest)) => {
-                        let start_val = start.get_val(*var);
-                        let end_val = end.get_val(*var);
-                        match op {
+                        let axis = "xmas".find(*var).unwrap();
+                        let val = *val as i64;
+                        let (matching, not_matching) = match op {
                             '>' => {
-                                if start_val > *val {
-                                    (Some((start.clone(), end.clone(), dest.as_str())), None)
-                                } else if end_val > *val {
-                                    (
-                                        Some((start.replace(*var, val + 1), end.clone(), dest.as_str())),
-                                        Some((start, end.replace(*var, *val))),
-                                    )
-                                } else {
-                                    (None, Some((start, end)))
-                                }
-                            }
-                            '<' => {
-                                if end_val < *val {
-                                    (Some((start.clone(), end.clone(), dest.as_str())), None)
-                                } else if start_val < *val {
-                                    (
-                                        Some((start.clone(), end.replace(*var, val - 1), dest.as_str())),
-                                        Some((start.replace(*var, *val), end)),
-                                    )
-                                } else {
-                                    (None, Some((start, end)))
-                                }
+                                let (below, above) = range.split_at(axis, val + 1);
+                                (above, below)
                             }
+                            '<' => range.split_at(axis, val),
                             _ => panic!(),
-                        }
+                        };
+                        rest = not_matching;
+                        (matching, dest)
                     }
                 };
-                if let Some((s, e, dest)) = destination {
-                    if dest == "A" {
-                        final_ranges.push((s, e));
-                    } else if dest != "R" {
-                        ranges.push((s, e, dest));
-                    }
-                }
-                match new_range {
-                    Some((new_start, new_end)) => {
-                        start = new_start;
-                        end = new_end;
-                    }
-                    None => {
-                        break;
+                match (matching, dest.as_str()) {
+                    (None, _) | (_, "R") => (),
+                    (Some(matching), "A") => final_ranges.push(matching),
+                    (Some(matching), dest) => {
+                        if path.contains(&dest) {
+                            return Err(workflow_cycle(dest));
+                        }
+                        let mut path = path.clone();
+                        path.push(dest);
+                        ranges.push((matching, dest, path));
                     }
                 }
             }
         }
 
-        let total_combinations: u64 = final_ranges
-            .into_iter()
-            .map(|(start, end)| {
-                ((end.x - start.x + 1) as u64)
-                    * ((end.m - start.m + 1) as u64)
-                    * ((end.a - start.a + 1) as u64)
-                    * ((end.s - start.s + 1) as u64)
-            })
-            .sum();
+        let total_combinations: i64 = final_ranges.iter().map(HyperRect::volume).sum();
+
+        Ok(total_combinations.into())
+    }
 
-        println!("{total_combinations}");
-        format!("{total_combinations}")
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(get_workflows_parts(input).map(|_| ()))
     }
 }
 
-fn get_workflows_parts(input: &str) -> (HashMap<String, Vec<Filter>>, Vec<Part>) {
-    let re_empty_line = Regex::new(r"\n[ ]*\n").unwrap();
-    let input: Vec<Vec<&str>> = re_empty_line.split(input).map(|s| s.split_ascii_whitespace().collect()).collect();
+fn workflow_cycle(name: &str) -> Error {
+    Error::no_solution(format!("workflow cycle through `{name}`"))
+}
 
-    let re_workflow = Regex::new(r"(?<name>.+)\{(?<filters>.+)\}").unwrap();
-    let workflows: HashMap<String, Vec<Filter>> = input[0]
-        .iter()
-        .map(|line| {
-            let cap = re_workflow.captures(line).unwrap();
-            let name = cap["name"].to_string();
-            let filters = cap["filters"]
-                .split(',')
-                .map(|s| match s.split_once(':') {
-                    Some((a, dest)) => {
-                        let var = a.chars().nth(0).unwrap();
-                        let op = a.chars().nth(1).unwrap();
-                        let val = a[2..].parse().unwrap();
-                        Filter::Map((var, op, val, dest.to_string()))
-                    }
-                    None => Filter::Destination(s.to_string()),
-                })
-                .collect();
-            (name, filters)
-        })
-        .collect();
-    let parts: Vec<Part> = input[1]
-        .iter()
-        .map(|line| {
-            let line = &line[1..(line.len() - 1)];
-            let values: Vec<u32> = line
-                .split(',')
-                .map(|s| {
-                    let (_, val) = s.split_once('=').unwrap();
-                    val.parse().unwrap()
-                })
-                .collect();
-            Part {
-                x: values[0],
-                m: values[1],
-                a: values[2],
-                s: values[3],
-            }
-        })
-        .collect();
+fn get_workflow<'a>(workflows: &'a Workflows, name: &str) -> Result<&'a Vec<Filter>, Error> {
+    workflows.get(name).ok_or(Error::no_solution(format!("no workflow named `{name}`")))
+}
+
+fn get_workflows_parts(input: &str) -> Result<(Workflows, Vec<Part>), Error> {
+    let blocks = input_blocks(input);
+    let [workflow_lines, part_lines] = &blocks[..] else {
+        return Err(Error::end_of_input(input, "expected a block of workflows and a block of parts"));
+    };
+
+    let mut workflows: Workflows = HashMap::new();
+    for line in workflow_lines {
+        let workflow_format = || line.error(line.text, "expected `<name>{<rules>}`");
+        let (name, rules) = line.text.split_once('{').ok_or_else(workflow_format)?;
+        let rules = rules.strip_suffix('}').ok_or_else(workflow_format)?;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase()) || rules.is_empty() {
+            return Err(workflow_format());
+        }
+        let filters = rules.split(',').map(|s| parse_filter(line, s)).collect::<Result<_, _>>()?;
+        workflows.insert(name.to_string(), filters);
+    }
 
-    (workflows, parts)
+    let mut parts: Vec<Part> = Vec::new();
+    for line in part_lines {
+        let ratings = line
+            .delimited(line.text, "{", "}")
+            .map_err(|_| line.error(line.text, "expected `{x=<x>,m=<m>,a=<a>,s=<s>}`"))?;
+        let values: Vec<u32> = line
+            .key_values(ratings, ',', "=")?
+            .into_iter()
+            .map(|(_, val)| line.parse(val, "a rating"))
+            .collect::<Result<_, _>>()?;
+        let [x, m, a, s] = values[..] else {
+            return Err(line.error(ratings, format!("expected 4 ratings, found {}", values.len())));
+        };
+        parts.push(Part { x, m, a, s });
+    }
+
+    Ok((workflows, parts))
+}
+
+fn parse_filter(line: &Line, s: &str) -> Result<Filter, Error> {
+    match s.split_once(':') {
+        Some((condition, dest)) => {
+            let mut chars = condition.chars();
+            let var = chars.next().filter(|c| "xmas".contains(*c));
+            let op = chars.next().filter(|c| *c == '<' || *c == '>');
+            let (Some(var), Some(op)) = (var, op) else {
+                return Err(line.error(condition, format!("expected a condition like `a<2006`, found `{condition}`")));
+            };
+            let val = line.parse(&condition[2..], "a rating")?;
+            Ok(Filter::Map((var, op, val, dest.to_string())))
+        }
+        None => Ok(Filter::Destination(s.to_string())),
+    }
 }
 
 #[cfg(test)]
@@ -230,50 +215,15 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part_one() {
-        let input = "
-        px{a<2006:qkq,m>2090:A,rfg}
-        pv{a>1716:R,A}
-        lnx{m>1548:A,A}
-        rfg{s<537:gd,x>2440:R,A}
-        qs{s>3448:A,lnx}
-        qkq{x<1416:A,crn}
-        crn{x>2662:A,R}
-        in{s<1351:px,qqz}
-        qqz{s>2770:qs,m<1801:hdj,R}
-        gd{a>3333:R,R}
-        hdj{m>838:A,pv}
-        
-        {x=787,m=2655,a=1222,s=2876}
-        {x=1679,m=44,a=2067,s=496}
-        {x=2036,m=264,a=79,s=2244}
-        {x=2461,m=1339,a=466,s=291}
-        {x=2127,m=1623,a=2188,s=1013}";
-        let value = Day.part_one(input);
-        assert_eq!(value, "19114");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        px{a<2006:qkq,m>2090:A,rfg}
-        pv{a>1716:R,A}
-        lnx{m>1548:A,A}
-        rfg{s<537:gd,x>2440:R,A}
-        qs{s>3448:A,lnx}
-        qkq{x<1416:A,crn}
-        crn{x>2662:A,R}
-        in{s<1351:px,qqz}
-        qqz{s>2770:qs,m<1801:hdj,R}
-        gd{a>3333:R,R}
-        hdj{m>838:A,pv}
-        
-        {x=787,m=2655,a=1222,s=2876}
-        {x=1679,m=44,a=2067,s=496}
-        {x=2036,m=264,a=79,s=2244}
-        {x=2461,m=1339,a=466,s=291}
-        {x=2127,m=1623,a=2188,s=1013}";
-        let value = Day.part_two(input);
-        assert_eq!(value, "167409079868000");
+    fn test_workflow_cycle() {
+        let input = "in{a>1:in,A}\n\n{x=1,m=2,a=5,s=3}";
+        let cycle = Err(Error::no_solution("workflow cycle through `in`"));
+        assert_eq!(Day.part_one(input), cycle);
+        assert_eq!(Day.part_two(input), cycle);
+
+        // Going through the same workflow on separate branches is fine
+        let input = "in{a>1:px,px}\npx{A}\n\n{x=1,m=2,a=5,s=3}";
+        assert_eq!(Day.part_one(input), Ok(11.into()));
+        assert_eq!(Day.part_two(input), Ok((4000_i64.pow(4)).into()));
     }
 }
@@ -5,7 +5,8 @@ use std::{
     vec,
 };
 
-use aoc2023::filter_input_lines;
+use crate::math::crt;
+use crate::{input_lines, Answer, Error};
 
 use super::Problem;
 
@@ -15,9 +16,13 @@ trait Module: Debug {
         None
     }
     fn outputs(&self) -> Vec<String>;
+    /// What the module remembers between pulses.
+    fn memory(&self) -> Vec<State> {
+        vec![]
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum State {
     High,
     Low,
@@ -73,6 +78,9 @@ impl Module for FlipFlop {
     fn outputs(&self) -> Vec<String> {
         self.outputs.clone()
     }
+    fn memory(&self) -> Vec<State> {
+        vec![self.state]
+    }
 }
 
 #[derive(Debug)]
@@ -107,6 +115,9 @@ impl Module for Conjunction {
     fn outputs(&self) -> Vec<String> {
         self.outputs.clone()
     }
+    fn memory(&self) -> Vec<State> {
+        self.inputs.values().copied().collect()
+    }
 }
 
 #[derive(Debug)]
@@ -130,14 +141,13 @@ impl Module for Broadcaster {
     }
 }
 
-#[derive(Debug)]
-struct Button;
+pub const TITLE: &str = "Pulse Propagation";
 
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let mut modules = get_modules(input);
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let mut modules = get_modules(input)?;
 
         let mut pulses: VecDeque<Pulse> = VecDeque::new();
         let mut num_low_pulses = 0;
@@ -163,17 +173,16 @@ impl Problem for Day {
         }
 
         let value = num_low_pulses * num_high_pulses;
-        println!("{value}");
-        format!("{value}")
+        Ok(value.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let mut modules = get_modules(input);
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let mut modules = get_modules(input)?;
 
         let rx_con = modules
             .iter()
             .find(|(_, module)| module.outputs().contains(&"rx".to_owned()))
-            .unwrap()
+            .ok_or(Error::no_solution("no module sends pulses to rx"))?
             .0
             .clone();
         let con_inputs: Vec<String> = modules
@@ -186,11 +195,31 @@ impl Problem for Day {
                 }
             })
             .collect();
+        if con_inputs.is_empty() {
+            return Err(Error::no_solution(format!("no module sends pulses to {rx_con}")));
+        }
 
+        // The presses on which each input sends a high pulse to the conjunction, until it has done so twice
         let mut pulses: VecDeque<Pulse> = VecDeque::new();
         let mut num_presses = 0;
-        let mut con_cycles = HashMap::new();
+        let mut high_presses: HashMap<String, Vec<i64>> = HashMap::new();
+        // Once the modules are back in an earlier state the presses repeat, so an input with a high pulse in the
+        // repeating presses sends its second one within another round of them, and the others never do
+        let mut names: Vec<String> = modules.keys().cloned().collect();
+        names.sort();
+        let mut seen_states: HashMap<Vec<State>, i64> = HashMap::new();
+        let mut last_press = None;
         loop {
+            if last_press == Some(num_presses) {
+                return Err(Error::no_solution(format!("the inputs of {rx_con} never all send two high pulses")));
+            }
+            if last_press.is_none() {
+                let memory = names.iter().flat_map(|name| modules[name].memory()).collect();
+                if let Some(previous) = seen_states.insert(memory, num_presses) {
+                    last_press = Some(2 * num_presses - previous);
+                }
+            }
+
             pulses.push_back(Pulse {
                 state: State::Low,
                 from: "button".to_string(),
@@ -198,8 +227,11 @@ impl Problem for Day {
             });
             num_presses += 1;
             while let Some(pulse) = pulses.pop_front() {
-                if pulse.to == rx_con && pulse.state == State::High && !con_cycles.contains_key(&pulse.to) {
-                    con_cycles.insert(pulse.from.to_owned(), num_presses);
+                if pulse.to == rx_con && pulse.state == State::High {
+                    let presses = high_presses.entry(pulse.from.to_owned()).or_default();
+                    if presses.len() < 2 && presses.last() != Some(&num_presses) {
+                        presses.push(num_presses);
+                    }
                 }
                 if let Some(module) = modules.get_mut(&pulse.to) {
                     for pulse in module.send_pulses(pulse) {
@@ -207,29 +239,36 @@ impl Problem for Day {
                     }
                 }
             }
-            if con_inputs.iter().all(|name| con_cycles.contains_key(name)) {
+            if con_inputs.iter().all(|name| high_presses.get(name).is_some_and(|presses| presses.len() == 2)) {
                 break;
             }
         }
 
-        let num_presses: u64 = con_cycles.iter().map(|(_, val)| *val as u64).product();
+        // Assuming each input keeps repeating with the period between its first two high pulses, the conjunction
+        // gets high pulses from all of them on the first press after all have started that fits all the periods
+        let first_press = high_presses.values().map(|presses| presses[0]).max().unwrap();
+        let (remainder, modulus) = crt(high_presses.values().map(|presses| (presses[0], presses[1] - presses[0])))
+            .ok_or(Error::no_solution("the inputs of the conjunction never send high pulses on the same press"))?;
+        let num_presses = first_press + (remainder - first_press).rem_euclid(modulus);
 
-        println!("{num_presses}");
-        format!("{num_presses}")
+        Ok(num_presses.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(get_modules(input).map(|_| ()))
     }
 }
 
-fn get_modules(input: &str) -> HashMap<String, Box<dyn Module>> {
-    let lines = filter_input_lines(input);
+fn get_modules(input: &str) -> Result<HashMap<String, Box<dyn Module>>, Error> {
     let mut modules: HashMap<String, Box<dyn Module>> = HashMap::new();
-    for line in lines {
-        let (inp, out) = line.split_once("->").unwrap();
+    for line in input_lines(input) {
+        let (inp, out) = line.split_once(line.text, "->")?;
         let outputs: Vec<String> = out.split(',').map(|s| s.trim().to_string()).collect();
         let (module, name): (Box<dyn Module>, String) = match inp.trim() {
             "broadcaster" => (Box::new(Broadcaster { outputs }), "broadcaster".to_string()),
             s => {
-                let name = s[1..].to_string();
-                match s.chars().nth(0).unwrap() {
+                let name = s.get(1..).unwrap_or_default().to_string();
+                match s.chars().next().unwrap_or_default() {
                     '%' => (
                         Box::new(FlipFlop {
                             state: State::Low,
@@ -246,7 +285,7 @@ fn get_modules(input: &str) -> HashMap<String, Box<dyn Module>> {
                         }),
                         name,
                     ),
-                    _ => panic!(),
+                    _ => return Err(line.error(s, format!("expected `broadcaster`, `%<name>` or `&<name>`, found `{s}`"))),
                 }
             }
         };
@@ -264,7 +303,7 @@ fn get_modules(input: &str) -> HashMap<String, Box<dyn Module>> {
         }
     }
 
-    modules
+    Ok(modules)
 }
 
 #[cfg(test)]
@@ -273,23 +312,19 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part_one() {
-        let input = "
-        broadcaster -> a, b, c
-        %a -> b
-        %b -> c
-        %c -> inv
-        &inv -> a";
-        let value = Day.part_one(input);
-        assert_eq!(value, "32000000");
-
-        let input = "
-        broadcaster -> a
-        %a -> inv, con
-        &inv -> b
-        %b -> con
-        &con -> output";
-        let value = Day.part_one(input);
-        assert_eq!(value, "11687500");
+    fn test_part_two_errors() {
+        let no_inputs = "broadcaster -> b\n%a -> rx\n%b -> b";
+        assert_eq!(Day.part_two(no_inputs), Err(Error::no_solution("no module sends pulses to a")));
+
+        // The broadcaster only ever sends low pulses
+        let never_high = "broadcaster -> a\n&a -> rx";
+        assert_eq!(
+            Day.part_two(never_high),
+            Err(Error::no_solution("the inputs of a never all send two high pulses"))
+        );
+
+        // Both flip-flops send a high pulse every other press, starting with the first
+        let repeating = "broadcaster -> a, b\n%a -> c\n%b -> c\n&c -> rx";
+        assert_eq!(Day.part_two(repeating), Ok(1.into()));
     }
 }
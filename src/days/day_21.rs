@@ -1,27 +1,47 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 
-use aoc2023::filter_input_lines;
+use crate::geom::Point2;
+use crate::grid::{Grid, Pos};
+use crate::search::{bfs_distances, Neighbours};
+use crate::{Answer, Error};
 
 use super::Problem;
 
+type Positions = HashSet<Pos>;
+
+/// The garden as a state space for walking between the plots, the rocks in it marked with `true`.
+struct Garden<'a>(&'a Grid<bool>);
+
+impl Neighbours for Garden<'_> {
+    type State = Pos;
+
+    fn neighbours(&self, &pos: &Pos) -> Vec<(Pos, u64)> {
+        self.0.neighbours4(pos).filter(|&next| !self.0[next]).map(|next| (next, 1)).collect()
+    }
+}
+
+pub const TITLE: &str = "Step Counter";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let final_positions = get_final_positions_part1(input, 64);
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let final_positions = get_final_positions_part1(input, 64)?;
         let num_positions = final_positions.len();
-        println!("{num_positions}");
-        format!("{num_positions}")
+        Ok(num_positions.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let (rocks, start_pos, width, height) = parse_input(input);
-        let (evens, odds) = get_even_odd_squares(start_pos, &rocks, width, height, usize::MAX);
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let (rocks, start_pos) = parse_input(input)?;
+        let (width, height) = (rocks.width(), rocks.height());
+        let (evens, odds) = get_even_odd_squares(start_pos, &rocks, usize::MAX);
         let num_odds = odds.len();
         let num_evens = evens.len();
 
         let total_steps = 26501365;
-        assert_eq!(width, height);
+        if width != height {
+            return Err(Error::no_solution("the garden is not square"));
+        }
         let num_map_steps = total_steps / width;
         let num_even_maps = (num_map_steps - 1).pow(2);
         let num_odd_maps = num_map_steps.pow(2);
@@ -33,7 +53,7 @@ impl Problem for Day {
         let side_odd_squares: usize = vec![(middle, width - 1), (height - 1, middle), (middle, 0), (0, middle)]
             .into_iter()
             .map(|pos| {
-                let (e, _) = get_even_odd_squares(pos, &rocks, width, height, num_steps_left);
+                let (e, _) = get_even_odd_squares(pos, &rocks, num_steps_left);
                 e.len()
             })
             .sum();
@@ -43,75 +63,53 @@ impl Problem for Day {
         let corner_odd_squares: usize = vec![(height - 1, width - 1), (height - 1, 0), (0, 0), (0, width - 1)]
             .into_iter()
             .map(|pos| {
-                let (e1, _) = get_even_odd_squares(pos, &rocks, width, height, num_steps_left1);
-                let (_, o2) = get_even_odd_squares(pos, &rocks, width, height, num_steps_left2);
+                let (e1, _) = get_even_odd_squares(pos, &rocks, num_steps_left1);
+                let (_, o2) = get_even_odd_squares(pos, &rocks, num_steps_left2);
                 num_map_steps * e1.len() + (num_map_steps - 1) * o2.len()
             })
             .sum();
 
         let num_garden_squares = complete_map_squares + side_odd_squares + corner_odd_squares;
-        println!("{num_garden_squares}");
-        format!("{num_garden_squares}")
+        Ok(num_garden_squares.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(parse_input(input).map(|_| ()))
     }
 }
 
-fn get_final_positions_part1(input: &str, num_steps: usize) -> Vec<(usize, usize)> {
-    let (rocks, start_pos, width, height) = parse_input(input);
+fn get_final_positions_part1(input: &str, num_steps: usize) -> Result<Vec<Pos>, Error> {
+    let (rocks, start_pos) = parse_input(input)?;
 
-    let reachable_positions = get_reachable_positions(start_pos, &rocks, width, height, num_steps);
+    let reachable_positions = get_reachable_positions(start_pos, &rocks, num_steps);
 
-    let parity = (num_steps as usize) % 2;
-    let final_positions: Vec<(usize, usize)> = reachable_positions
+    let parity = (num_steps % 2) as i64;
+    let final_positions: Vec<Pos> = reachable_positions
         .into_iter()
         .filter(|pos| {
-            let dist = start_pos.0.abs_diff(pos.0) + start_pos.1.abs_diff(pos.1);
+            let dist = Point2::from(start_pos).manhattan(Point2::from(*pos));
             (dist % 2) == parity
         })
         .collect();
 
-    // print_board(&final_positions, &rocks, width, height);
-
-    final_positions
+    Ok(final_positions)
 }
 
-fn get_reachable_positions(
-    start_pos: (usize, usize),
-    rocks: &HashSet<(usize, usize)>,
-    width: usize,
-    height: usize,
-    max_steps: usize,
-) -> HashSet<(usize, usize)> {
-    let mut to_visit: VecDeque<((usize, usize), usize)> = vec![(start_pos, 0)].into_iter().collect();
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-
-    while let Some((pos, time_step)) = to_visit.pop_front() {
-        if time_step >= max_steps {
-            continue;
-        }
-        for neighbour in get_neighbours(pos, &rocks, width, height) {
-            if !visited.contains(&neighbour) {
-                to_visit.push_back((neighbour, time_step + 1));
-                visited.insert(neighbour);
-            }
-        }
-    }
-
-    visited
+fn get_reachable_positions(start_pos: Pos, rocks: &Grid<bool>, max_steps: usize) -> Positions {
+    bfs_distances(&Garden(rocks), start_pos)
+        .into_iter()
+        .filter(|&(_, steps)| steps <= max_steps)
+        .map(|(pos, _)| pos)
+        .collect()
 }
 
-fn get_even_odd_squares(
-    start_pos: (usize, usize),
-    rocks: &HashSet<(usize, usize)>,
-    width: usize,
-    height: usize,
-    max_steps: usize,
-) -> (HashSet<(usize, usize)>, HashSet<(usize, usize)>) {
-    let reachable_positions = get_reachable_positions(start_pos, rocks, width, height, max_steps);
+fn get_even_odd_squares(start_pos: Pos, rocks: &Grid<bool>, max_steps: usize) -> (Positions, Positions) {
+    let reachable_positions = get_reachable_positions(start_pos, rocks, max_steps);
 
     let mut evens = HashSet::new();
     let mut odds = HashSet::new();
     for pos in reachable_positions.into_iter() {
-        let dist = start_pos.0.abs_diff(pos.0) + start_pos.1.abs_diff(pos.1);
+        let dist = Point2::from(start_pos).manhattan(Point2::from(pos));
         if (dist % 2) == 0 {
             evens.insert(pos);
         } else {
@@ -122,67 +120,13 @@ fn get_even_odd_squares(
     (evens, odds)
 }
 
-fn parse_input(input: &str) -> (HashSet<(usize, usize)>, (usize, usize), usize, usize) {
-    let lines = filter_input_lines(input);
-    let height = lines.len();
-    let width = lines[0].len();
-
-    let mut rocks = HashSet::new();
-    let mut start_pos = (0, 0);
-    for (i, line) in lines.iter().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            match c {
-                '#' => {
-                    rocks.insert((i, j));
-                }
-                'S' => {
-                    start_pos = (i, j);
-                }
-                _ => (),
-            }
-        }
-    }
-
-    (rocks, start_pos, width, height)
-}
-
-fn get_neighbours(pos: (usize, usize), rocks: &HashSet<(usize, usize)>, width: usize, height: usize) -> Vec<(usize, usize)> {
-    let width = width as i32;
-    let height = height as i32;
-    vec![(-1, 0), (1, 0), (0, -1), (0, 1)]
-        .into_iter()
-        .filter_map(|offset| {
-            let new_pos = (pos.0 as i32 + offset.0, pos.1 as i32 + offset.1);
-            if new_pos.0 < 0 || new_pos.0 >= height || new_pos.1 < 0 || new_pos.1 >= width {
-                return None;
-            }
-            let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-            if rocks.contains(&new_pos) {
-                None
-            } else {
-                Some(new_pos)
-            }
-        })
-        .collect()
-}
-
-#[allow(dead_code)]
-fn print_board(positions: &Vec<(usize, usize)>, rocks: &HashSet<(usize, usize)>, width: usize, height: usize) {
-    let mut board = String::new();
-    for i in 0..height {
-        for j in 0..width {
-            let pos = (i, j);
-            if positions.contains(&pos) {
-                board.push('O');
-            } else if rocks.contains(&pos) {
-                board.push('#');
-            } else {
-                board.push('.');
-            }
-        }
-        board.push('\n');
-    }
-    println!("{board}");
+/// Returns a grid telling where the rocks are, and the start position.
+fn parse_input(input: &str) -> Result<(Grid<bool>, Pos), Error> {
+    let tiles = Grid::parse(input, |c| ".#S".contains(c).then_some(c))?;
+    let start_pos = tiles
+        .position(|&c| c == 'S')
+        .ok_or(Error::end_of_input(input, "no start position `S` in the garden"))?;
+    Ok((tiles.map(|&c| c == '#'), start_pos))
 }
 
 #[cfg(test)]
@@ -204,7 +148,7 @@ mod tests {
         .##.#.####.
         .##..##.##.
         ...........";
-        let final_positions = get_final_positions_part1(input, 6);
+        let final_positions = get_final_positions_part1(input, 6).unwrap();
         assert_eq!(final_positions.len(), 16);
     }
 
@@ -1,9 +1,6 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    str::FromStr,
-};
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use aoc2023::filter_input_lines;
+use crate::{input_lines, Answer, Error, Line};
 use itertools::Itertools;
 
 use super::Problem;
@@ -16,22 +13,28 @@ struct Brick {
     supported_by: HashSet<usize>,
 }
 
-impl FromStr for Brick {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once('~').unwrap();
-        let start: Vec<usize> = start.split(',').map(|s| s.parse().unwrap()).collect();
-        let end: Vec<usize> = end.split(',').map(|s| s.parse().unwrap()).collect();
+impl Brick {
+    fn parse(line: &Line) -> Result<Self, Error> {
+        let (start, end) = line.split_once(line.text, "~")?;
+        let parse_coords = |part: &str| -> Result<(usize, usize, usize), Error> {
+            match line.parse_separated(part, ',', "a coordinate")?[..] {
+                [x, y, z] => Ok((x, y, z)),
+                _ => Err(line.error(part, format!("expected three coordinates, found `{part}`"))),
+            }
+        };
+        let start = parse_coords(start)?;
+        let end = parse_coords(end)?;
+        if start.0 > end.0 || start.1 > end.1 || start.2 > end.2 {
+            return Err(line.error(line.text, "brick ends before it starts"));
+        }
         Ok(Brick {
-            start: (start[0], start[1], start[2]),
-            end: (end[0], end[1], end[2]),
+            start,
+            end,
             supports: HashSet::new(),
             supported_by: HashSet::new(),
         })
     }
-}
 
-impl Brick {
     fn coords(&self) -> Vec<(usize, usize, usize)> {
         let mut coords = Vec::new();
         for x in self.start.0..=self.end.0 {
@@ -48,11 +51,13 @@ impl Brick {
     }
 }
 
+pub const TITLE: &str = "Sand Slabs";
+
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let bricks: Vec<Brick> = filter_input_lines(input).iter().map(|line| line.parse().unwrap()).collect();
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let bricks: Vec<Brick> = input_lines(input).iter().map(Brick::parse).collect::<Result<_, _>>()?;
         let bricks = drop_bricks(bricks);
 
         let num_bricks: usize = bricks
@@ -66,12 +71,11 @@ impl Problem for Day {
             })
             .count();
 
-        println!("{num_bricks}");
-        format!("{num_bricks}")
+        Ok(num_bricks.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let bricks: Vec<Brick> = filter_input_lines(input).iter().map(|line| line.parse().unwrap()).collect();
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let bricks: Vec<Brick> = input_lines(input).iter().map(Brick::parse).collect::<Result<_, _>>()?;
         let bricks = drop_bricks(bricks);
 
         let total_fallen_bricks: usize = bricks
@@ -92,13 +96,16 @@ impl Problem for Day {
             })
             .sum();
 
-        println!("{total_fallen_bricks}");
-        format!("{total_fallen_bricks}")
+        Ok(total_fallen_bricks.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(input_lines(input).iter().try_for_each(|line| Brick::parse(line).map(|_| ())))
     }
 }
 
 fn drop_bricks(mut bricks: Vec<Brick>) -> Vec<Brick> {
-    bricks.sort_by(|a, b| a.zmin().cmp(&b.zmin()));
+    bricks.sort_by_key(|a| a.zmin());
     let mut dropped_zmax: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
     let mut dropped_bricks: Vec<Brick> = Vec::new();
     for (i, brick) in bricks.iter().enumerate() {
@@ -137,37 +144,3 @@ fn drop_bricks(mut bricks: Vec<Brick>) -> Vec<Brick> {
     }
     dropped_bricks
 }
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let input = "
-        1,0,1~1,2,1
-        0,0,2~2,0,2
-        0,2,3~2,2,3
-        0,0,4~0,2,4
-        2,0,5~2,2,5
-        0,1,6~2,1,6
-        1,1,8~1,1,9";
-        let value = Day.part_one(input);
-        assert_eq!(value, "5");
-    }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        1,0,1~1,2,1
-        0,0,2~2,0,2
-        0,2,3~2,2,3
-        0,0,4~0,2,4
-        2,0,5~2,2,5
-        0,1,6~2,1,6
-        1,1,8~1,1,9";
-        let value = Day.part_two(input);
-        assert_eq!(value, "7");
-    }
-}
@@ -1,10 +1,5 @@
-use std::{
-    fmt::Display,
-    ops::{Div, Neg, Mul, Add},
-};
-
-use aoc2023::filter_input_lines;
-use regex::Regex;
+use crate::num::{solve_linear, Rational};
+use crate::{input_lines, Answer, Error};
 
 use super::Problem;
 
@@ -18,163 +13,54 @@ struct Hailstone {
     vz: i128,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Fraction {
-    int: i128,
-    num: i128,
-    den: i128,
-}
-
-impl Display for Fraction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}+{}/{}", self.int, self.num, self.den)
-    }
-}
-
-impl Neg for Fraction {
-    type Output = Fraction;
-    fn neg(self) -> Self::Output {
-        Fraction {
-            int: -self.int,
-            num: -self.num,
-            den: self.den,
-        }.reduce()
-    }
-}
-
-impl Div for Fraction {
-    type Output = Fraction;
-    fn div(self, rhs: Self) -> Self::Output {
-        let frac1 = Fraction {
-            int: 0,
-            num: self.int * rhs.den,
-            den: rhs.int * rhs.den + rhs.num,
-        }.reduce();
-        let gcd = greatest_common_divisor(self.den, rhs.den);
-        let rhs_den = rhs.den / gcd;
-        let self_den = self.den / gcd;
-        let frac2 = Fraction {
-            int: 0,
-            num: self.num * rhs_den,
-            den: rhs.int * self.den * rhs_den + self_den * rhs.num,
-        }.reduce();
-        frac1 + frac2
-    }
-}
-
-impl Mul for Fraction {
-    type Output = Fraction;
-    fn mul(self, rhs: Self) -> Self::Output {
-        let frac1 = Fraction {
-            int: self.int * rhs.int,
-            num: self.num * rhs.num,
-            den: self.den * rhs.den,
-        }.reduce();
-        let frac2 = Fraction {
-            int: 0,
-            num: self.int * rhs.num,
-            den: rhs.den,
-        }.reduce();
-        let frac3 = Fraction {
-            int: 0,
-            num: rhs.int * self.num,
-            den: self.den,
-        }.reduce();
-        frac1 + frac2 + frac3
-    }
-}
-
-impl Add for Fraction {
-    type Output = Fraction;
-    fn add(self, rhs: Self) -> Self::Output {
-        let gcd = greatest_common_divisor(self.den, rhs.den);
-        let rhs_den = rhs.den / gcd;
-        let self_den = self.den / gcd;
-        Fraction {
-            int: self.int + rhs.int,
-            num: self.num * rhs_den + rhs.num * self_den,
-            den: self.den * rhs_den
-        }.reduce()
-    }
-}
-
-impl Fraction {
-    fn reduce(mut self) -> Self {
-        let gcd = greatest_common_divisor(self.num, self.den);
-        self.num /= gcd;
-        self.den /= gcd;
-        if self.den < 0 {
-            self.num *= -1;
-            self.den *= -1;
-        }
-        if self.num.abs() >= self.den.abs() {
-            self.int += self.num / self.den;
-            self.num %= self.den;
-        }
-        self
-    }
-    fn as_int(&self) -> Option<i128> {
-        let frac = self.reduce();
-        if !(frac.den == 1 && frac.num == 0) {
-            None
-        } else {
-            Some(self.int)
-        }
-    }
-}
-
-fn greatest_common_divisor(a: i128, b: i128) -> i128 {
-    if b == 0 {
-        a
-    } else {
-        greatest_common_divisor(b, a % b)
-    }
-}
+pub const TITLE: &str = "Never Tell Me The Odds";
 
 pub struct Day;
 
 impl Problem for Day {
-    fn part_one(&self, input: &str) -> String {
-        let hailstones = parse_input(input);
+    fn part_one(&self, input: &str) -> Result<Answer, Error> {
+        let hailstones = parse_input(input)?;
         let num_collisions = find_collisions(&hailstones, 200000000000000.0, 400000000000000.0);
-        println!("{num_collisions}");
-        format!("{num_collisions}")
+        Ok(num_collisions.into())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let hailstones = parse_input(input);
+    fn part_two(&self, input: &str) -> Result<Answer, Error> {
+        let hailstones = parse_input(input)?;
         let hailstones = find_nonparallel_hailstones(hailstones);
+        if hailstones.len() < 3 {
+            return Err(Error::no_solution("fewer than three non-parallel hailstones"));
+        }
         let aug_mat = construct_augmented_matrix(hailstones);
-        let rock = gaussian_elimination(aug_mat);
-        let coord_sum = rock[0] + rock[1] + rock[2];
-        println!("{coord_sum}");
-        format!("{coord_sum}")
+        let rock = solve_linear(aug_mat).ok_or(Error::no_solution("no unique rock trajectory"))?;
+        let coord_sum: i128 = rock[..3]
+            .iter()
+            .map(|coord| coord.to_integer())
+            .sum::<Option<i128>>()
+            .ok_or(Error::no_solution("rock position is not an integer"))?;
+        Ok(coord_sum.into())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(parse_input(input).map(|_| ()))
     }
 }
 
-fn parse_input(input: &str) -> Vec<Hailstone> {
-    let lines = filter_input_lines(input);
-    let re = Regex::new(r"[+-]?\d+").unwrap();
-    lines
+fn parse_input(input: &str) -> Result<Vec<Hailstone>, Error> {
+    input_lines(input)
         .into_iter()
         .map(|line| {
-            let cap: Vec<i128> = re.find_iter(line).map(|m| m.as_str().parse().unwrap()).collect();
-            Hailstone {
-                x: cap[0],
-                y: cap[1],
-                z: cap[2],
-                vx: cap[3],
-                vy: cap[4],
-                vz: cap[5],
-            }
+            let [x, y, z, vx, vy, vz] = line.ints(line.text)?[..] else {
+                return Err(line.error(line.text, "expected `<x>, <y>, <z> @ <vx>, <vy>, <vz>`"));
+            };
+            Ok(Hailstone { x, y, z, vx, vy, vz })
         })
         .collect()
 }
 
-fn find_collisions(hailstones: &Vec<Hailstone>, min_time: f64, max_time: f64) -> u32 {
+fn find_collisions(hailstones: &[Hailstone], min_time: f64, max_time: f64) -> u32 {
     let len = hailstones.len();
     let mut num_collisions = 0;
-    for i in 0..(len - 1) {
+    for i in 0..len {
         for j in (i + 1)..len {
             let a = &hailstones[i];
             let b = &hailstones[j];
@@ -217,7 +103,7 @@ fn find_nonparallel_hailstones(hailstones: Vec<Hailstone>) -> Vec<Hailstone> {
     hailstones_nonparallel
 }
 
-fn construct_augmented_matrix(hailstones: Vec<Hailstone>) -> Vec<Vec<i128>> {
+fn construct_augmented_matrix(hailstones: Vec<Hailstone>) -> Vec<Vec<Rational>> {
     let mut aug_mat = Vec::new();
     for j_ind in [1, 2] {
         let hi = &hailstones[0];
@@ -229,65 +115,11 @@ fn construct_augmented_matrix(hailstones: Vec<Hailstone>) -> Vec<Vec<i128>> {
         let b1 = viy * pix - vix * piy - vjy * pjx + vjx * pjy;
         let b2 = viz * pix - vix * piz - vjz * pjx + vjx * pjz;
         let b3 = viz * piy - viy * piz - vjz * pjy + vjy * pjz;
-        aug_mat.push(vec![(viy - vjy), -(vix - vjx), 0, -(piy - pjy), (pix - pjx), 0, b1]);
-        aug_mat.push(vec![(viz - vjz), 0, -(vix - vjx), -(piz - pjz), 0, (pix - pjx), b2]);
-        aug_mat.push(vec![0, (viz - vjz), -(viy - vjy), 0, -(piz - pjz), (piy - pjy), b3]);
+        aug_mat.push([(viy - vjy), -(vix - vjx), 0, -(piy - pjy), (pix - pjx), 0, b1]);
+        aug_mat.push([(viz - vjz), 0, -(vix - vjx), -(piz - pjz), 0, (pix - pjx), b2]);
+        aug_mat.push([0, (viz - vjz), -(viy - vjy), 0, -(piz - pjz), (piy - pjy), b3]);
     }
-    aug_mat
-}
-
-fn gaussian_elimination(aug_mat: Vec<Vec<i128>>) -> Vec<i128> {
-    let mut aug_mat: Vec<Vec<Fraction>> = aug_mat
-        .into_iter()
-        .map(|row| row.into_iter().map(|n| Fraction { int: n, num: 0, den: 1 }).collect())
-        .collect();
-
-    let num_rows = aug_mat.len();
-    let num_cols = aug_mat[0].len();
-
-    for col in 0..(num_cols - 2) {
-
-        // Swap rows so that current diagonal element is non-zero
-        let diag_elem = aug_mat[col][col];
-        if diag_elem.int == 0 && diag_elem.num == 0 {
-            for i in (col + 1)..num_rows {
-                let col_elem = aug_mat[i][col];
-                if !(col_elem.int == 0 && col_elem.num == 0) {
-                    aug_mat.swap(i, col);
-                    break;
-                }
-            }
-        }
-
-        let a_row = aug_mat[col].clone();
-        let a = a_row[col];
-
-        // Subtract current diagonal row from lower rows
-        for row in (col + 1)..num_rows {
-            let b = aug_mat[row][col];
-            if b.int == 0 && b.num == 0 {
-                continue;
-            }
-            let m = -b / a;
-            for j in 0..num_cols {
-                aug_mat[row][j] = aug_mat[row][j] + m * a_row[j];
-            }
-        }
-    }
-
-    // Do substitution in reverse
-    let mut x = vec![Fraction { int: 0, num: 0, den: 1 }; num_rows];
-    for row in (0..num_rows).rev() {
-        let a = aug_mat[row][row];
-        let mut b = aug_mat[row].last().unwrap().clone();
-        for col in (row + 1)..(num_cols - 1) {
-            b = b + -aug_mat[row][col] * x[col];
-        }
-        x[row] = b / a;
-    }
-
-    x.into_iter().map(|v| v.as_int().unwrap()).collect()
-
+    aug_mat.into_iter().map(|row| row.map(Rational::from).to_vec()).collect()
 }
 
 #[cfg(test)]
@@ -297,26 +129,9 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let input = "
-        19, 13, 30 @ -2,  1, -2
-        18, 19, 22 @ -1, -1, -2
-        20, 25, 34 @ -2, -2, -4
-        12, 31, 28 @ -1, -2, -1
-        20, 19, 15 @  1, -5, -3";
-        let hailstones = parse_input(input);
+        let input = include_str!("../../fixtures/day_24/example.txt");
+        let hailstones = parse_input(input).unwrap();
         let num_collisions = find_collisions(&hailstones, 7.0, 27.0);
         assert_eq!(num_collisions, 2);
     }
-
-    #[test]
-    fn test_part_two() {
-        let input = "
-        19, 13, 30 @ -2,  1, -2
-        18, 19, 22 @ -1, -1, -2
-        20, 25, 34 @ -2, -2, -4
-        12, 31, 28 @ -1, -2, -1
-        20, 19, 15 @  1, -5, -3";
-        let value = Day.part_two(input);
-        assert_eq!(value, "47");
-    }
 }
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+/// Error returned by the puzzle solutions.
+///
+/// The day is not known where the error is created, so it is left empty and filled in by the runner with
+/// [`Error::with_day`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input could not be parsed. Line and column are 1-based positions in the input text.
+    Parse {
+        day: Option<i32>,
+        line: usize,
+        column: usize,
+        description: String,
+    },
+    /// The input was parsed but the puzzle has no answer for it.
+    NoSolution { day: Option<i32>, description: String },
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, description: impl Into<String>) -> Self {
+        Error::Parse {
+            day: None,
+            line,
+            column,
+            description: description.into(),
+        }
+    }
+
+    /// Parse error for input that ends before everything expected was found.
+    pub fn end_of_input(input: &str, description: impl Into<String>) -> Self {
+        Error::parse(input.lines().count() + 1, 1, description)
+    }
+
+    pub fn no_solution(description: impl Into<String>) -> Self {
+        Error::NoSolution {
+            day: None,
+            description: description.into(),
+        }
+    }
+
+    pub fn with_day(mut self, day_num: i32) -> Self {
+        match &mut self {
+            Error::Parse { day, .. } | Error::NoSolution { day, .. } => *day = Some(day_num),
+        }
+        self
+    }
+
+    pub fn day(&self) -> Option<i32> {
+        match self {
+            Error::Parse { day, .. } | Error::NoSolution { day, .. } => *day,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day() {
+            write!(f, "day {day}, ")?;
+        }
+        match self {
+            Error::Parse {
+                line,
+                column,
+                description,
+                ..
+            } => write!(f, "line {line}, column {column}: {description}"),
+            Error::NoSolution { description, .. } => write!(f, "no solution: {description}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::parse(3, 7, "expected a number, found `x`");
+        assert_eq!(err.to_string(), "line 3, column 7: expected a number, found `x`");
+        let err = err.with_day(5);
+        assert_eq!(err.day(), Some(5));
+        assert_eq!(err.to_string(), "day 5, line 3, column 7: expected a number, found `x`");
+        let err = Error::no_solution("no path").with_day(23);
+        assert_eq!(err.to_string(), "day 23, no solution: no path");
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let err = Error::end_of_input("a\nb\nc", "missing section");
+        assert_eq!(err, Error::parse(4, 1, "missing section"));
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+
+use crate::answers::{key_error, parse_parts, parse_table};
+use crate::{Answer, Error};
+
+pub const EXPECTED_FILE: &str = "expected.toml";
+
+/// An example input and the answers expected for it.
+///
+/// Each day has its examples in `fixtures/day_XX/`, one `<case>.txt` per input and an `expected.toml` with
+/// one table of answers per case:
+///
+/// ```toml
+/// [example]
+/// part_1 = 142
+/// ```
+///
+/// A case without a table has no expected answers, and a part missing from a table is not checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub name: String,
+    pub input: String,
+    pub expected: BTreeMap<i32, Answer>,
+}
+
+pub fn fixture_dir(day_num: i32) -> PathBuf {
+    PathBuf::from(format!("./fixtures/day_{day_num:02}"))
+}
+
+/// Loads all the cases in `dir`, sorted by name. A missing directory has no cases.
+pub fn load_fixtures(dir: &Path) -> Result<Vec<Fixture>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let expected_path = dir.join(EXPECTED_FILE);
+    let mut expected = match fs::read_to_string(&expected_path) {
+        Ok(text) => parse_expected(&text).map_err(|err| format!("{}: {err}", expected_path.display()))?,
+        Err(_) => BTreeMap::new(),
+    };
+
+    let entries = fs::read_dir(dir).map_err(|e| format!("Could not read {}: {e}", dir.display()))?;
+    let mut fixtures = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Could not read {}: {e}", dir.display()))?.path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let input = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+        let expected = expected.remove(&name).unwrap_or_default();
+        fixtures.push(Fixture { name, input, expected });
+    }
+
+    if let Some(name) = expected.keys().next() {
+        return Err(format!("{}: no input file for case `{name}`", expected_path.display()));
+    }
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
+
+/// Parses the expected answers of each case from the contents of `expected.toml`.
+pub fn parse_expected(text: &str) -> Result<BTreeMap<String, BTreeMap<i32, Answer>>, Error> {
+    let table = parse_table(text)?;
+    let mut expected = BTreeMap::new();
+    for (name, parts) in table.iter() {
+        let Value::Table(parts) = parts else {
+            return Err(key_error(text, name, format!("expected a table of parts for `{name}`")));
+        };
+        expected.insert(name.clone(), parse_parts(text, parts)?.into_iter().collect());
+    }
+    Ok(expected)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_expected() {
+        let expected = parse_expected("[example]\npart_1 = 142\n\n[example_2]\npart_2 = \"EFGH\"\n").unwrap();
+        assert_eq!(expected["example"], BTreeMap::from([(1, Answer::Int(142))]));
+        assert_eq!(expected["example_2"], BTreeMap::from([(2, Answer::Text("EFGH".to_string()))]));
+
+        assert_eq!(
+            parse_expected("example = 142\n"),
+            Err(Error::parse(1, 1, "expected a table of parts for `example`"))
+        );
+        assert_eq!(
+            parse_expected("[example]\npart = 1\n"),
+            Err(Error::parse(2, 1, "expected `part_<number>`, found `part`"))
+        );
+    }
+
+    #[test]
+    fn test_load_fixtures() {
+        let dir = std::env::temp_dir().join(format!("aoc2023_fixtures_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("example.txt"), "1abc2\n").unwrap();
+        fs::write(dir.join("extra.txt"), "a1b\n").unwrap();
+        fs::write(dir.join("notes.md"), "not an input").unwrap();
+        fs::write(dir.join(EXPECTED_FILE), "[example]\npart_1 = 12\n").unwrap();
+
+        let fixtures = load_fixtures(&dir).unwrap();
+        assert_eq!(fixtures.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["example", "extra"]);
+        assert_eq!(fixtures[0].input, "1abc2\n");
+        assert_eq!(fixtures[0].expected, BTreeMap::from([(1, Answer::Int(12))]));
+        assert!(fixtures[1].expected.is_empty());
+
+        fs::write(dir.join(EXPECTED_FILE), "[missing]\npart_1 = 12\n").unwrap();
+        assert!(load_fixtures(&dir).unwrap_err().contains("no input file for case `missing`"));
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(load_fixtures(&dir), Ok(Vec::new()));
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::grid::Pos;
+
+/// Point or vector in the plane. `y` grows downwards like the rows of the input, so [`Direction4::Up`] is
+/// `(0, -1)`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(self, other: Point2) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn neighbours4(self) -> [Point2; 4] {
+        Direction4::ALL.map(|dir| self + dir.offset())
+    }
+
+    pub fn neighbours8(self) -> [Point2; 8] {
+        Direction8::ALL.map(|dir| self + dir.offset())
+    }
+
+    /// Grid position of the point, if it is not left of or above the grid.
+    pub fn to_pos(self) -> Option<Pos> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+}
+
+/// Point at the column and row of a grid position.
+impl From<Pos> for Point2 {
+    fn from((row, col): Pos) -> Self {
+        Point2::new(col as i64, row as i64)
+    }
+}
+
+/// Point or vector in space.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(self, other: Point3) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+/// Implements the arithmetic operators component-wise.
+macro_rules! impl_point_ops {
+    ($point:ident, $($field:ident),+) => {
+        impl Add for $point {
+            type Output = $point;
+
+            fn add(self, other: $point) -> $point {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = $point;
+
+            fn sub(self, other: $point) -> $point {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = $point;
+
+            fn mul(self, factor: i64) -> $point {
+                $point { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = $point;
+
+            fn neg(self) -> $point {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: $point) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: $point) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2, x, y);
+impl_point_ops!(Point3, x, y, z);
+
+impl Display for Point2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// One of the four orthogonal directions, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    pub const ALL: [Direction4; 4] = [Direction4::Up, Direction4::Right, Direction4::Down, Direction4::Left];
+
+    pub fn offset(self) -> Point2 {
+        match self {
+            Direction4::Up => Point2::new(0, -1),
+            Direction4::Right => Point2::new(1, 0),
+            Direction4::Down => Point2::new(0, 1),
+            Direction4::Left => Point2::new(-1, 0),
+        }
+    }
+
+    /// Row and column offset for moving in a [`Grid`](crate::grid::Grid).
+    pub fn grid_offset(self) -> (isize, isize) {
+        let offset = self.offset();
+        (offset.y as isize, offset.x as isize)
+    }
+
+    pub fn turn_right(self) -> Self {
+        Direction4::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Direction4::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Direction4::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Parses `U`, `R`, `D` and `L` or the compass directions `N`, `E`, `S` and `W`.
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'U' | 'N' => Some(Direction4::Up),
+            'R' | 'E' => Some(Direction4::Right),
+            'D' | 'S' => Some(Direction4::Down),
+            'L' | 'W' => Some(Direction4::Left),
+            _ => None,
+        }
+    }
+
+    /// Parses `^`, `>`, `v` and `<`.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction4::Up),
+            '>' => Some(Direction4::Right),
+            'v' => Some(Direction4::Down),
+            '<' => Some(Direction4::Left),
+            _ => None,
+        }
+    }
+}
+
+/// Error from parsing a string that is not a direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirectionError {
+    pub found: String,
+}
+
+impl Display for ParseDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown direction `{}`", self.found)
+    }
+}
+
+impl std::error::Error for ParseDirectionError {}
+
+/// Parses a letter or an arrow, see [`Direction4::from_letter`] and [`Direction4::from_arrow`].
+impl FromStr for Direction4 {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let direction = match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction4::from_letter(c).or(Direction4::from_arrow(c)),
+            _ => None,
+        };
+        direction.ok_or_else(|| ParseDirectionError { found: s.to_string() })
+    }
+}
+
+/// One of the eight compass directions, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::N,
+        Direction8::NE,
+        Direction8::E,
+        Direction8::SE,
+        Direction8::S,
+        Direction8::SW,
+        Direction8::W,
+        Direction8::NW,
+    ];
+
+    pub fn offset(self) -> Point2 {
+        match self {
+            Direction8::N => Point2::new(0, -1),
+            Direction8::NE => Point2::new(1, -1),
+            Direction8::E => Point2::new(1, 0),
+            Direction8::SE => Point2::new(1, 1),
+            Direction8::S => Point2::new(0, 1),
+            Direction8::SW => Point2::new(-1, 1),
+            Direction8::W => Point2::new(-1, 0),
+            Direction8::NW => Point2::new(-1, -1),
+        }
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        Direction8::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns 45 degrees counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Direction8::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Direction8::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Parses the arrows `↑`, `↗`, `→`, `↘`, `↓`, `↙`, `←` and `↖`.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        "↑↗→↘↓↙←↖".chars().position(|arrow| arrow == c).map(|i| Direction8::ALL[i])
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(dir: Direction4) -> Self {
+        Direction8::ALL[dir as usize * 2]
+    }
+}
+
+/// Parses the compass directions `N`, `NE`, `E`, ... or an arrow, see [`Direction8::from_arrow`].
+impl FromStr for Direction8 {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        if let Some(i) = names.iter().position(|name| *name == s) {
+            return Ok(Direction8::ALL[i]);
+        }
+        let mut chars = s.chars();
+        let direction = match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction8::from_arrow(c),
+            _ => None,
+        };
+        direction.ok_or_else(|| ParseDirectionError { found: s.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_points() {
+        let p = Point2::new(3, -2);
+        assert_eq!(p + Direction4::Up.offset() * 2, Point2::new(3, -4));
+        assert_eq!(-p, Point2::new(-3, 2));
+        assert_eq!(p.manhattan(Point2::ORIGIN), 5);
+        assert_eq!(p.to_pos(), None);
+        assert_eq!(Point2::from((4, 1)), Point2::new(1, 4));
+        assert_eq!(Point2::new(1, 4).to_pos(), Some((4, 1)));
+        assert_eq!(Point2::ORIGIN.neighbours4()[1], Point2::new(1, 0));
+
+        let mut q = Point3::new(1, 2, 3);
+        q -= Point3::new(2, 2, 2);
+        assert_eq!(q, Point3::new(-1, 0, 1));
+        assert_eq!(q.manhattan(Point3::ORIGIN), 2);
+        assert_eq!(q.to_string(), "(-1, 0, 1)");
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction4::Up.turn_right(), Direction4::Right);
+        assert_eq!(Direction4::Up.turn_left(), Direction4::Left);
+        assert_eq!(Direction4::Left.opposite(), Direction4::Right);
+        assert_eq!(Direction4::Down.grid_offset(), (1, 0));
+        assert_eq!("R".parse(), Ok(Direction4::Right));
+        assert_eq!("v".parse(), Ok(Direction4::Down));
+        let err = "x".parse::<Direction4>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown direction `x`");
+
+        assert_eq!(Direction8::N.turn_left(), Direction8::NW);
+        assert_eq!(Direction8::NE.opposite(), Direction8::SW);
+        assert_eq!(Direction8::from(Direction4::Left), Direction8::W);
+        assert_eq!("SE".parse(), Ok(Direction8::SE));
+        assert_eq!("↙".parse(), Ok(Direction8::SW));
+        for dir in Direction8::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.offset() + dir.opposite().offset(), Point2::ORIGIN);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::search::Neighbours;
+
+/// Weighted directed graph with the nodes numbered from 0, stored as adjacency lists.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Graph {
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl Graph {
+    pub fn new(num_nodes: usize) -> Self {
+        Graph {
+            edges: vec![Vec::new(); num_nodes],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Adds a node without edges and returns it.
+    pub fn add_node(&mut self) -> usize {
+        self.edges.push(Vec::new());
+        self.edges.len() - 1
+    }
+
+    /// Adds an edge, or raises the weight of the existing edge from `from` to `to` to `weight`.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: u64) {
+        match self.edges[from].iter_mut().find(|(node, _)| *node == to) {
+            Some((_, old_weight)) => *old_weight = weight.max(*old_weight),
+            None => self.edges[from].push((to, weight)),
+        }
+    }
+
+    /// Edges leaving `node` and their weights.
+    pub fn edges(&self, node: usize) -> &[(usize, u64)] {
+        &self.edges[node]
+    }
+}
+
+/// A graph contracted from a state space, with the state that each node stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contracted<S> {
+    pub graph: Graph,
+    pub nodes: Vec<S>,
+}
+
+impl<S: Eq> Contracted<S> {
+    pub fn node(&self, state: &S) -> Option<usize> {
+        self.nodes.iter().position(|node| node == state)
+    }
+}
+
+/// Contracts the corridors of a state space reachable from the `keep` states into single edges.
+///
+/// The nodes are the `keep` states, in order, and every state from which there is more than one way forward
+/// without turning back. The edges follow the corridors between them, with the total cost of the moves as the
+/// weight. Corridors that end in a state with no way forward are left out, as are the ones leading back to where
+/// they started and the ones going around a loop without a branch.
+pub fn contract<N: Neighbours>(space: &N, keep: &[N::State]) -> Contracted<N::State> {
+    let mut nodes: Vec<N::State> = keep.to_vec();
+    let mut index: HashMap<N::State, usize> = nodes.iter().cloned().zip(0..).collect();
+    let mut graph = Graph::new(nodes.len());
+
+    let mut node = 0;
+    while node < nodes.len() {
+        for (first, first_cost) in space.neighbours(&nodes[node]) {
+            let (mut prev, mut state, mut cost) = (nodes[node].clone(), first, first_cost);
+            let mut corridor = HashSet::new();
+            let end = loop {
+                if let Some(&end) = index.get(&state) {
+                    break Some(end);
+                }
+                if !corridor.insert(state.clone()) {
+                    break None;
+                }
+                let mut forward: Vec<(N::State, u64)> =
+                    space.neighbours(&state).into_iter().filter(|(next, _)| *next != prev).collect();
+                match forward.len() {
+                    0 => break None,
+                    1 => {
+                        let (next, next_cost) = forward.pop().unwrap();
+                        prev = std::mem::replace(&mut state, next);
+                        cost += next_cost;
+                    }
+                    _ => {
+                        index.insert(state.clone(), nodes.len());
+                        nodes.push(state);
+                        graph.add_node();
+                        break Some(nodes.len() - 1);
+                    }
+                }
+            };
+            match end {
+                Some(end) if end != node => graph.add_edge(node, end, cost),
+                _ => (),
+            }
+        }
+        node += 1;
+    }
+
+    Contracted { graph, nodes }
+}
+
+/// Length of the longest path from `start` to `end` that visits every node at most once, or `None` if `end` can't
+/// be reached. Searches all the paths depth first, skipping the ones that can't beat the longest path so far.
+pub fn longest_path(graph: &Graph, start: usize, end: usize) -> Option<u64> {
+    // The most that entering each node can add to a path, for bounding what the unvisited nodes can add
+    let mut max_in = vec![0; graph.len()];
+    for node in 0..graph.len() {
+        for &(next, weight) in graph.edges(node) {
+            max_in[next] = max_in[next].max(weight);
+        }
+    }
+
+    // A path reaching the only node leading to `end` has to go to `end` next, or it can't get there anymore
+    let mut before_end = (0..graph.len()).filter(|&node| graph.edges(node).iter().any(|&(next, _)| next == end));
+    let last = match (before_end.next(), before_end.next()) {
+        (Some(last), None) => Some(last),
+        _ => None,
+    };
+
+    let mut search = LongestPath {
+        graph,
+        end,
+        last,
+        max_in,
+        visited: vec![false; graph.len()],
+        best: None,
+    };
+    let remaining = search.max_in.iter().sum::<u64>() - search.max_in[start];
+    search.visited[start] = true;
+    search.visit(start, 0, remaining);
+    search.best
+}
+
+struct LongestPath<'a> {
+    graph: &'a Graph,
+    end: usize,
+    last: Option<usize>,
+    max_in: Vec<u64>,
+    visited: Vec<bool>,
+    best: Option<u64>,
+}
+
+impl LongestPath<'_> {
+    /// `remaining` is the sum of `max_in` over the unvisited nodes.
+    fn visit(&mut self, node: usize, length: u64, remaining: u64) {
+        if node == self.end {
+            self.best = Some(self.best.map_or(length, |best| best.max(length)));
+            return;
+        }
+        if self.best.is_some_and(|best| length + remaining <= best) {
+            return;
+        }
+        for &(next, weight) in self.graph.edges(node) {
+            if Some(node) == self.last && next != self.end {
+                continue;
+            }
+            if !self.visited[next] {
+                self.visited[next] = true;
+                self.visit(next, length + weight, remaining - self.max_in[next]);
+                self.visited[next] = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    struct Maze(Grid<char>);
+
+    impl Neighbours for Maze {
+        type State = Pos;
+
+        fn neighbours(&self, &pos: &Pos) -> Vec<(Pos, u64)> {
+            self.0
+                .neighbours4(pos)
+                .filter(|&next| self.0[next] == '.')
+                .map(|next| (next, 1))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_contract() {
+        let maze = Maze(Grid::parse(".....\n.#.#.\n.....\n.#.##\n.#...", Some).unwrap());
+        let contracted = contract(&maze, &[(0, 0), (4, 4)]);
+        // The dead end at (4, 0) is left out
+        assert_eq!(contracted.nodes, [(0, 0), (4, 4), (2, 0), (0, 2), (2, 2)]);
+        // Of the two corridors from (0, 2) to (2, 2) only the longer one is kept
+        let mut edges = contracted.graph.edges(3).to_vec();
+        edges.sort();
+        assert_eq!(edges, [(0, 2), (4, 6)]);
+
+        // Around the top right corner and down
+        assert_eq!(longest_path(&contracted.graph, 0, 1), Some(12));
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let mut graph = Graph::new(5);
+        for (from, to, weight) in [(0, 1, 1), (0, 2, 5), (1, 2, 1), (1, 3, 10), (2, 3, 1), (3, 4, 1)] {
+            graph.add_edge(from, to, weight);
+            graph.add_edge(to, from, weight);
+        }
+        // 0 -> 2 -> 1 -> 3 -> 4
+        assert_eq!(longest_path(&graph, 0, 4), Some(17));
+        assert_eq!(longest_path(&graph, 0, 0), Some(0));
+        graph.add_node();
+        assert_eq!(longest_path(&graph, 0, 5), None);
+
+        let mut chain = Graph::new(100);
+        for node in 1..100 {
+            chain.add_edge(node - 1, node, 2);
+        }
+        assert_eq!(longest_path(&chain, 0, 99), Some(198));
+    }
+
+    struct Directed(&'static [(u32, u32)]);
+
+    impl Neighbours for Directed {
+        type State = u32;
+
+        fn neighbours(&self, &node: &u32) -> Vec<(u32, u64)> {
+            self.0.iter().filter(|&&(from, _)| from == node).map(|&(_, to)| (to, 1)).collect()
+        }
+    }
+
+    #[test]
+    fn test_contract_loop() {
+        // 1 -> 2 -> 3 -> 1 goes around without a branch, and 9 is never reached
+        let contracted = contract(&Directed(&[(0, 1), (1, 2), (2, 3), (3, 1)]), &[0, 9]);
+        assert_eq!(contracted.nodes, [0, 9]);
+        assert!(contracted.graph.edges(0).is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::geom::Direction4;
+use crate::{input_lines, Error, Line};
+
+/// Position in a grid as `(row, col)`.
+pub type Pos = (usize, usize);
+
+/// Row and column offsets of the four orthogonal neighbours: up, down, left and right.
+pub const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Row and column offsets of all eight neighbours, row by row.
+pub const OFFSETS_8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A rectangular grid of cells stored row by row.
+///
+/// Indexing with a [`Pos`] panics outside the grid, [`Grid::get`] and the neighbour methods check the bounds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid from its cells row by row. Panics if there are not `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "wrong number of cells for a {width}x{height} grid");
+        Grid { width, height, cells }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses a rectangular grid with one character per cell. `cell` maps each character to a cell, or to
+    /// `None` if the character is not allowed.
+    pub fn parse(input: &str, cell: impl FnMut(char) -> Option<T>) -> Result<Self, Error> {
+        let lines = input_lines(input);
+        if lines.is_empty() {
+            return Err(Error::end_of_input(input, "expected a grid"));
+        }
+        Grid::from_lines(&lines, cell)
+    }
+
+    /// Same as [`Grid::parse`] for input that is already split into lines, such as a block of the input.
+    pub fn from_lines(lines: &[Line], mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, Error> {
+        let width = lines.first().map(|line| line.text.chars().count()).unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in lines.iter() {
+            let mut row_width = 0;
+            for (index, c) in line.text.chars().enumerate() {
+                cells.push(cell(c).ok_or_else(|| line.error_at(index, format!("unexpected tile `{c}`")))?);
+                row_width += 1;
+            }
+            if row_width != width {
+                return Err(line.error(line.text, format!("expected {width} tiles, found {row_width}")));
+            }
+        }
+        Ok(Grid::new(width, lines.len(), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos).then(|| &mut self.cells[pos.0 * self.width + pos.1])
+    }
+
+    /// The position `offset` away from `pos`, if it is in the grid.
+    pub fn offset(&self, (row, col): Pos, (d_row, d_col): (isize, isize)) -> Option<Pos> {
+        let pos = (row.checked_add_signed(d_row)?, col.checked_add_signed(d_col)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The next position from `pos` in direction `dir`, if it is in the grid.
+    pub fn step(&self, pos: Pos, dir: Direction4) -> Option<Pos> {
+        self.offset(pos, dir.grid_offset())
+    }
+
+    /// Orthogonal neighbours of `pos` in the grid, in the order of [`OFFSETS_4`].
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS_4.into_iter().filter_map(move |offset| self.offset(pos, offset))
+    }
+
+    /// All neighbours of `pos` in the grid including the diagonal ones, in the order of [`OFFSETS_8`].
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS_8.into_iter().filter_map(move |offset| self.offset(pos, offset))
+    }
+
+    /// All positions row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// All cells with their positions row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell, row by row, for which `predicate` holds.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(pos, _)| pos)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` would panic on a zero width grid
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {col} outside a grid of width {}", self.width);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.col(col))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Grid with the rows and columns swapped.
+    pub fn transposed(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(self.height, self.width, self.cols().flatten().cloned().collect())
+    }
+
+    /// Grid rotated 90 degrees clockwise, so that the first column becomes the first row reversed.
+    pub fn rotated_cw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|col| (0..self.height).rev().map(move |row| self[(row, col)].clone()))
+            .collect();
+        Grid::new(self.height, self.width, cells)
+    }
+
+    /// Grid rotated 90 degrees counterclockwise, so that the last column becomes the first row.
+    pub fn rotated_ccw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width).rev().flat_map(|col| self.col(col)).cloned().collect();
+        Grid::new(self.height, self.width, cells)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("position {pos:?} outside a {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("position {pos:?} outside a {width}x{height} grid"),
+        }
+    }
+}
+
+/// One line per row with the cells next to each other.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("#.\n.#\n..", |c| "#.".contains(c).then_some(c)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "#.\n.#\n..");
+        assert_eq!(grid.position(|c| *c == '#'), Some((0, 0)));
+
+        let tile = |c| "#.".contains(c).then_some(c);
+        assert_eq!(Grid::parse("#.\n.x", tile), Err(Error::parse(2, 2, "unexpected tile `x`")));
+        assert_eq!(Grid::parse("#.\n.", tile), Err(Error::parse(2, 1, "expected 2 tiles, found 1")));
+        assert!(Grid::parse("", tile).is_err());
+    }
+
+    #[test]
+    fn test_views() {
+        // 1 2 3
+        // 4 5 6
+        let grid = Grid::new(3, 2, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.col(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.transposed(), Grid::new(2, 3, vec![1, 4, 2, 5, 3, 6]));
+        assert_eq!(grid.rotated_cw(), Grid::new(2, 3, vec![4, 1, 5, 2, 6, 3]));
+        assert_eq!(grid.rotated_ccw(), Grid::new(2, 3, vec![3, 6, 2, 5, 1, 4]));
+        assert_eq!(grid.rotated_cw().rotated_ccw(), grid);
+
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(
+            grid.neighbours8((1, 1)).collect::<Vec<_>>(),
+            [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]
+        );
+        assert_eq!(grid.offset((1, 2), (0, 1)), None);
+        assert_eq!(grid.step((1, 2), Direction4::Up), Some((0, 2)));
+        assert_eq!(grid.map(|n| n * 2)[(1, 2)], 12);
+    }
+}
@@ -0,0 +1,359 @@
+/// Half-open interval `start..end` of integers, empty if `start >= end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub const fn new(start: i64, end: i64) -> Self {
+        Interval { start, end }
+    }
+
+    pub const fn with_len(start: i64, len: i64) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    /// Interval of all the values an `i64` can take, except `i64::MAX`.
+    pub const fn all() -> Self {
+        Interval::new(i64::MIN, i64::MAX)
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The common part of the intervals, if they overlap.
+    pub fn intersect(&self, other: &Interval) -> Option<Interval> {
+        let interval = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!interval.is_empty()).then_some(interval)
+    }
+
+    /// The parts before `at` and from `at` on, if they are not empty.
+    pub fn split_at(&self, at: i64) -> (Option<Interval>, Option<Interval>) {
+        let below = Interval::new(self.start, self.end.min(at));
+        let above = Interval::new(self.start.max(at), self.end);
+        ((!below.is_empty()).then_some(below), (!above.is_empty()).then_some(above))
+    }
+
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+
+/// Set of integers, stored as sorted intervals that neither overlap nor touch.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|interval| interval.end - 1)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= value);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if !interval.is_empty() {
+            self.intervals.push(interval);
+            self.normalize();
+        }
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals.iter().chain(other.intervals.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .flat_map(|a| other.intervals.iter().filter_map(|b| a.intersect(b)))
+            .collect()
+    }
+
+    /// The integers in `self` but not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut parts = self.intervals.clone();
+        for b in other.intervals.iter() {
+            parts = parts
+                .into_iter()
+                .flat_map(|a| {
+                    let (below, _) = a.split_at(b.start);
+                    let (_, above) = a.split_at(b.end);
+                    [below, above].into_iter().flatten()
+                })
+                .collect();
+        }
+        parts.into_iter().collect()
+    }
+
+    /// The integers before `at` and from `at` on.
+    pub fn split_at(&self, at: i64) -> (IntervalSet, IntervalSet) {
+        let (below, above): (Vec<_>, Vec<_>) = self.intervals.iter().map(|interval| interval.split_at(at)).unzip();
+        (below.into_iter().flatten().collect(), above.into_iter().flatten().collect())
+    }
+
+    /// Sorts the intervals and merges the ones that overlap or touch.
+    fn normalize(&mut self) {
+        self.intervals.retain(|interval| !interval.is_empty());
+        self.intervals.sort();
+        let mut merged: Vec<Interval> = Vec::with_capacity(self.intervals.len());
+        for interval in self.intervals.drain(..) {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        self.intervals = merged;
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        IntervalSet::from_iter([interval])
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet {
+            intervals: iter.into_iter().collect(),
+        };
+        set.normalize();
+        set
+    }
+}
+
+/// Piecewise shift of the integers: the values in each source interval are moved by the interval's offset, and
+/// all the other values map to themselves.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RangeMap {
+    /// Sorted and not overlapping.
+    pieces: Vec<(Interval, i64)>,
+}
+
+impl RangeMap {
+    pub fn new() -> Self {
+        RangeMap::default()
+    }
+
+    /// The source intervals and their offsets, in order.
+    pub fn pieces(&self) -> &[(Interval, i64)] {
+        &self.pieces
+    }
+
+    /// The values with a mapping of their own.
+    pub fn sources(&self) -> IntervalSet {
+        self.pieces.iter().map(|&(source, _)| source).collect()
+    }
+
+    /// Maps the values in `source` by `offset`. The values that are already mapped keep their mapping.
+    pub fn insert(&mut self, source: Interval, offset: i64) {
+        let free = IntervalSet::from(source).difference(&self.sources());
+        self.pieces.extend(free.intervals().iter().map(|&interval| (interval, offset)));
+        self.pieces.sort();
+    }
+
+    pub fn get(&self, value: i64) -> i64 {
+        let index = self.pieces.partition_point(|(source, _)| source.end <= value);
+        match self.pieces.get(index) {
+            Some((source, offset)) if source.contains(value) => value + offset,
+            _ => value,
+        }
+    }
+
+    /// Splits `interval` into the parts that move by the same offset, with the offsets, in order.
+    pub fn split(&self, interval: Interval) -> Vec<(Interval, i64)> {
+        let mut parts = Vec::new();
+        let mut rest = interval;
+        for &(source, offset) in self.pieces.iter() {
+            let Some(part) = rest.intersect(&source) else {
+                continue;
+            };
+            if rest.start < part.start {
+                parts.push((Interval::new(rest.start, part.start), 0));
+            }
+            parts.push((part, offset));
+            rest.start = part.end;
+        }
+        if !rest.is_empty() {
+            parts.push((rest, 0));
+        }
+        parts
+    }
+
+    /// Where the values of `set` are mapped to.
+    pub fn map_set(&self, set: &IntervalSet) -> IntervalSet {
+        set.intervals()
+            .iter()
+            .flat_map(|&interval| self.split(interval))
+            .map(|(part, offset)| part.shift(offset))
+            .collect()
+    }
+
+    /// The values that are mapped into `set`. There can be more of them than in `set`, since several sources
+    /// can map to the same values.
+    pub fn preimage(&self, set: &IntervalSet) -> IntervalSet {
+        let unmapped = set.difference(&self.sources());
+        let mut preimage = unmapped.intervals().to_vec();
+        for &(source, offset) in self.pieces.iter() {
+            let shifted = set.intervals().iter().map(|interval| interval.shift(-offset));
+            preimage.extend(shifted.filter_map(|interval| interval.intersect(&source)));
+        }
+        preimage.into_iter().collect()
+    }
+
+    /// The map that applies `self` and then `then`.
+    pub fn compose(&self, then: &RangeMap) -> RangeMap {
+        let unmapped = IntervalSet::from(Interval::all()).difference(&self.sources());
+        let mut pieces: Vec<(Interval, i64)> = self
+            .pieces
+            .iter()
+            .copied()
+            .chain(unmapped.intervals().iter().map(|&interval| (interval, 0)))
+            .flat_map(|(source, offset)| {
+                then.split(source.shift(offset))
+                    .into_iter()
+                    .map(move |(part, then_offset)| (part.shift(-offset), offset + then_offset))
+            })
+            .filter(|&(_, offset)| offset != 0)
+            .collect();
+        pieces.sort();
+        RangeMap { pieces }
+    }
+}
+
+/// Axis-aligned box in `N` dimensions, with an interval for each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HyperRect<const N: usize> {
+    pub axes: [Interval; N],
+}
+
+impl<const N: usize> HyperRect<N> {
+    pub fn new(axes: [Interval; N]) -> Self {
+        HyperRect { axes }
+    }
+
+    /// Number of integer points in the box.
+    pub fn volume(&self) -> i64 {
+        self.axes.iter().map(Interval::len).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(Interval::is_empty)
+    }
+
+    pub fn contains(&self, point: &[i64; N]) -> bool {
+        self.axes.iter().zip(point).all(|(axis, &value)| axis.contains(value))
+    }
+
+    pub fn intersect(&self, other: &HyperRect<N>) -> Option<HyperRect<N>> {
+        let mut axes = self.axes;
+        for (axis, other_axis) in axes.iter_mut().zip(other.axes.iter()) {
+            *axis = axis.intersect(other_axis)?;
+        }
+        Some(HyperRect { axes })
+    }
+
+    /// The parts before `at` and from `at` on along `axis`, if they are not empty.
+    pub fn split_at(&self, axis: usize, at: i64) -> (Option<HyperRect<N>>, Option<HyperRect<N>>) {
+        let (below, above) = self.axes[axis].split_at(at);
+        let with_axis = |interval: Interval| {
+            let mut axes = self.axes;
+            axes[axis] = interval;
+            HyperRect { axes }
+        };
+        (below.map(with_axis), above.map(with_axis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_interval_set() {
+        let a: IntervalSet = [Interval::new(5, 8), Interval::new(0, 3), Interval::new(3, 4)]
+            .into_iter()
+            .collect();
+        assert_eq!(a.intervals(), [Interval::new(0, 4), Interval::new(5, 8)]);
+        assert_eq!(a.len(), 7);
+        assert_eq!((a.min(), a.max()), (Some(0), Some(7)));
+        assert!(a.contains(6) && !a.contains(4) && !a.contains(8));
+
+        let b = IntervalSet::from(Interval::new(2, 6));
+        assert_eq!(a.union(&b).intervals(), [Interval::new(0, 8)]);
+        assert_eq!(a.intersection(&b).intervals(), [Interval::new(2, 4), Interval::new(5, 6)]);
+        assert_eq!(a.difference(&b).intervals(), [Interval::new(0, 2), Interval::new(6, 8)]);
+        let (below, above) = a.split_at(6);
+        assert_eq!((below.len(), above.len()), (5, 2));
+
+        let cube = HyperRect::new([Interval::new(1, 5); 3]);
+        assert_eq!(cube.volume(), 64);
+        let (below, above) = cube.split_at(1, 2);
+        assert_eq!((below.unwrap().volume(), above.unwrap().volume()), (16, 48));
+        assert_eq!(cube.split_at(0, 9), (Some(cube), None));
+        assert!(cube.contains(&[1, 4, 2]) && !cube.contains(&[1, 5, 2]));
+    }
+
+    #[test]
+    fn test_range_map() {
+        let mut map = RangeMap::new();
+        map.insert(Interval::with_len(98, 2), -48);
+        map.insert(Interval::with_len(50, 48), 2);
+        map.insert(Interval::with_len(40, 20), 100);
+        assert_eq!(
+            [map.get(79), map.get(98), map.get(45), map.get(55), map.get(100)],
+            [81, 50, 145, 57, 100]
+        );
+
+        let seeds = IntervalSet::from(Interval::new(96, 101));
+        assert_eq!(map.map_set(&seeds).intervals(), [Interval::new(50, 52), Interval::new(98, 101)]);
+        let image = IntervalSet::from(Interval::new(50, 53));
+        assert_eq!(map.preimage(&image).intervals(), [Interval::new(50, 51), Interval::new(98, 100)]);
+        // 145 is not mapped, so it maps to itself as well as being the image of 45
+        let image = IntervalSet::from(Interval::new(145, 146));
+        assert_eq!(map.preimage(&image).intervals(), [Interval::new(45, 46), Interval::new(145, 146)]);
+
+        let mut then = RangeMap::new();
+        then.insert(Interval::new(0, 100), 1000);
+        let composed = map.compose(&then);
+        for value in [0, 45, 79, 97, 98, 99, 100, 150] {
+            assert_eq!(composed.get(value), then.get(map.get(value)));
+        }
+    }
+}
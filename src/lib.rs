@@ -1,21 +1,35 @@
-use std::fs;
+//! Advent of Code 2023 solutions. Every day is registered in [`days::DAYS`] and solved through its [`Problem`]
+//! implementation, and the input parsing helpers in [`parse`] are shared by the days.
 
-pub fn read_input(day_num: i32) -> String {
-    let input_path = format!("./inputs/day_{day_num:02}.txt");
-    fs::read_to_string(input_path).expect(&format!("No input file for day {day_num}"))
+use std::{fs, io};
+
+pub mod aho_corasick;
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+pub mod days;
+pub mod error;
+pub mod fixtures;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod num;
+pub mod parse;
+pub mod polygon;
+pub mod search;
+
+pub use answer::Answer;
+pub use days::{DayInfo, Problem};
+pub use error::Error;
+pub use parse::{input_blocks, input_lines, Line};
+
+pub fn input_path(day_num: i32) -> String {
+    format!("./inputs/day_{day_num:02}.txt")
 }
 
-pub fn filter_input_lines(input: &str) -> Vec<&str> {
-    input
-        .trim()
-        .lines()
-        .filter_map(|line| {
-            let line = line.trim();
-            if line.len() == 0 {
-                None
-            } else {
-                Some(line)
-            }
-        })
-        .collect::<Vec<&str>>()
+pub fn read_input(day_num: i32) -> io::Result<String> {
+    fs::read_to_string(input_path(day_num))
 }
@@ -1,87 +1,345 @@
 use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-use days::Problem;
-use aoc2023::read_input;
+use aoc2023::answers::{Answers, Verdict, ANSWERS_PATH};
+use aoc2023::bench::{bench_day, BenchReport, DayBench, Stats};
+use aoc2023::days::{self, DAYS};
+use aoc2023::{input_path, read_input, Answer, DayInfo, Error};
+use cli::{Args, Command, LAST_DAY, USAGE};
 
-mod days;
+mod cli;
+mod scaffold;
 
-fn parse_args() -> (i32, Vec<i32>) {
+struct PartResult {
+    day_num: i32,
+    part: i32,
+    answer: Result<Answer, Error>,
+    elapsed: Duration,
+}
 
-    let mut args = env::args();
-    
-    args.next();
-    let day_num = match args.next() {
-        None => panic!("No day number given"),
-        Some(a) => {
-            let num = match a.parse::<i32>() {
-                Ok(n) => n,
-                Err(_) => panic!("Invalid day number {a}")
-            };
-            num
+fn get_day(day_num: i32) -> Result<&'static DayInfo, String> {
+    days::get_day(day_num).ok_or(format!("Day {day_num} is not implemented yet"))
+}
+
+fn load_input(day_num: i32, input: &Option<PathBuf>) -> Result<String, String> {
+    match input {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display())),
+        None => read_input(day_num).map_err(|e| format!("Could not read {}: {e}", input_path(day_num))),
+    }
+}
+
+fn record_answers(results: &[PartResult]) -> Result<usize, String> {
+    let mut answers = Answers::load(ANSWERS_PATH)?;
+    let mut num_recorded = 0;
+    for result in results.iter() {
+        if let Ok(answer) = &result.answer {
+            answers.insert(result.day_num, result.part, answer.clone());
+            num_recorded += 1;
         }
+    }
+    answers.save(ANSWERS_PATH)?;
+    Ok(num_recorded)
+}
+
+fn run(day: &DayInfo, parts: &[i32], input: &str) -> Vec<PartResult> {
+    let mut results = Vec::new();
+    for &part in parts {
+        let start = Instant::now();
+        let answer = day.solve(part, input);
+        let elapsed = start.elapsed();
+        results.push(PartResult {
+            day_num: day.number,
+            part,
+            answer,
+            elapsed,
+        });
+    }
+    results
+}
+
+fn summary_answer(answer: &Result<Answer, Error>) -> String {
+    match answer {
+        Ok(Answer::Grid(rows)) => format!("<{}x{} grid>", rows.first().map(|r| r.len()).unwrap_or(0), rows.len()),
+        Ok(answer) => answer.to_string(),
+        Err(_) => "ERROR".to_string(),
+    }
+}
+
+fn print_summary(results: &[PartResult]) {
+    let answers: Vec<String> = results.iter().map(|r| summary_answer(&r.answer)).collect();
+    let answer_width = answers.iter().map(|a| a.len()).max().unwrap_or(0).max("Answer".len());
+    println!();
+    println!("Day | Part | {:answer_width$} | {:>12}", "Answer", "Time");
+    println!("----+------+-{}-+-{}", "-".repeat(answer_width), "-".repeat(12));
+    for (PartResult { day_num, part, answer: _, elapsed }, answer) in results.iter().zip(answers) {
+        let millis = elapsed.as_secs_f64() * 1000.0;
+        println!("{day_num:>3} | {part:>4} | {answer:answer_width$} | {millis:>9.3} ms");
+    }
+    let total_millis = results.iter().map(|r| r.elapsed).sum::<Duration>().as_secs_f64() * 1000.0;
+    println!("----+------+-{}-+-{}", "-".repeat(answer_width), "-".repeat(12));
+    println!("{:>3}   {:>4}   {:answer_width$}   {total_millis:>9.3} ms", "", "", "Total");
+}
+
+fn run_command(args: &Args) -> ExitCode {
+    let mut results = Vec::new();
+    let mut num_failed = 0;
+    for &day_num in args.day_nums.iter() {
+        let day = match get_day(day_num) {
+            Ok(day) => day,
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                num_failed += 1;
+                continue;
+            }
+        };
+        let input = match load_input(day_num, &args.input) {
+            Ok(input) => input,
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                return ExitCode::FAILURE;
+            }
+        };
+        for result in run(day, &args.parts, &input) {
+            match &result.answer {
+                Ok(answer) if args.quiet => println!("{answer}"),
+                Ok(Answer::Grid(rows)) => println!("Day {day_num}, part {}:\n{}", result.part, rows.join("\n")),
+                Ok(answer) => println!("Day {day_num}, part {}: {answer}", result.part),
+                Err(err) => {
+                    eprintln!("error in part {}: {err}", result.part);
+                    num_failed += 1;
+                }
+            }
+            results.push(result);
+        }
+    }
+    if args.day_nums.len() > 1 && !args.quiet {
+        print_summary(&results);
+    }
+    if args.record {
+        match record_answers(&results) {
+            Ok(num_recorded) if !args.quiet => println!("Recorded {num_recorded} answer(s) in {ANSWERS_PATH}"),
+            Ok(_) => (),
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if num_failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn print_bench_table(args: &Args, results: &[DayBench]) {
+    let run_s = if args.runs == 1 { "" } else { "s" };
+    let warmup_s = if args.warmup == 1 { "" } else { "s" };
+    println!("Times in ms over {} run{run_s} after {} warm-up run{warmup_s}", args.runs, args.warmup);
+    println!();
+    println!("Day | Part  | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}", "Min", "Median", "Mean", "Stddev", "Solve");
+    println!("----+-------+{}", vec!["-".repeat(12); 5].join("+"));
+    let print_row = |day_num: i32, part: &str, stats: &Stats, solve: Option<f64>| {
+        let solve = solve.map(|s| format!("{s:.3}")).unwrap_or_default();
+        println!(
+            "{day_num:>3} | {part:5} | {:>10.3} | {:>10.3} | {:>10.3} | {:>10.3} | {solve:>10}",
+            stats.min_ms, stats.median_ms, stats.mean_ms, stats.stddev_ms
+        );
     };
+    for result in results {
+        if let Some(parse) = &result.parse {
+            print_row(result.day, "parse", parse, None);
+        }
+        for part in result.parts.iter() {
+            print_row(result.day, &part.part.to_string(), &part.time, part.solve_median_ms);
+        }
+    }
+}
 
-    let parts = match args.next() {
-        None => vec![1, 2],
-        Some(a) => {
-            let num = match a.parse::<i32>() {
-                Ok(n) => n,
-                Err(_) => panic!("Invalid part number {a}")
-            };
-            if num <= 0 || num > 2 {
-                panic!("Invalid part number {num}");
+fn bench_command(args: &Args) -> ExitCode {
+    let mut results = Vec::new();
+    let mut num_failed = 0;
+    for &day_num in args.day_nums.iter() {
+        let day_input = get_day(day_num).and_then(|day| Ok((day, load_input(day_num, &args.input)?)));
+        let (day, input) = match day_input {
+            Ok(day_input) => day_input,
+            Err(msg) => {
+                eprintln!("warning: {msg}");
+                continue;
+            }
+        };
+        match bench_day(day, &args.parts, args.warmup, args.runs, &input) {
+            Ok(result) => results.push(result),
+            Err(err) => {
+                eprintln!("error: {err}");
+                num_failed += 1;
+            }
+        }
+    }
+
+    if args.json {
+        let report = BenchReport {
+            runs: args.runs,
+            warmup: args.warmup,
+            days: results,
+        };
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_bench_table(args, &results);
+    }
+
+    if num_failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn check_command(args: &Args) -> ExitCode {
+    // Answers are only known for the default inputs
+    let mut answers = if args.input.is_some() {
+        Answers::default()
+    } else {
+        match Answers::load(ANSWERS_PATH) {
+            Ok(answers) => answers,
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                return ExitCode::FAILURE;
             }
-            vec![num]
         }
     };
 
-    return (day_num, parts)
+    // Report panics as failed checks instead of letting the default hook print them
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let (mut num_passed, mut num_failed, mut num_unknown, mut num_skipped) = (0, 0, 0, 0);
+    for &day_num in args.day_nums.iter() {
+        let day_input = get_day(day_num).and_then(|day| Ok((day, load_input(day_num, &args.input)?)));
+        let (day, input) = match day_input {
+            Ok(day_input) => day_input,
+            Err(msg) => {
+                if !args.quiet {
+                    println!("Day {day_num:>2}: skipped ({msg})");
+                }
+                num_skipped += 1;
+                continue;
+            }
+        };
+        for &part in args.parts.iter() {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| day.solve(part, &input)));
+            let status = match result {
+                Ok(Ok(answer)) if args.record => {
+                    let status = format!("recorded ({})", summary_answer(&Ok(answer.clone())));
+                    answers.insert(day_num, part, answer);
+                    status
+                }
+                Ok(Ok(answer)) => match answers.verify(day_num, part, &answer) {
+                    Verdict::Pass => {
+                        num_passed += 1;
+                        format!("pass ({})", summary_answer(&Ok(answer)))
+                    }
+                    Verdict::Fail { expected } => {
+                        num_failed += 1;
+                        format!("FAILED (expected {}, got {})", summary_answer(&Ok(expected)), summary_answer(&Ok(answer)))
+                    }
+                    Verdict::Unknown => {
+                        num_unknown += 1;
+                        format!("unknown ({})", summary_answer(&Ok(answer)))
+                    }
+                },
+                Ok(Err(err)) => {
+                    num_failed += 1;
+                    format!("FAILED ({err})")
+                }
+                Err(payload) => {
+                    num_failed += 1;
+                    let msg = payload
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    format!("FAILED ({msg})")
+                }
+            };
+            if !args.quiet || status.starts_with("FAILED") {
+                println!("Day {day_num:>2}, part {part}: {status}");
+            }
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    if args.record {
+        if let Err(msg) = answers.save(ANSWERS_PATH) {
+            eprintln!("error: {msg}");
+            return ExitCode::FAILURE;
+        }
+    } else if !args.quiet {
+        println!("{num_passed} passed, {num_failed} failed, {num_unknown} unknown, {num_skipped} skipped");
+    }
 
+    if num_failed > 0 {
+        eprintln!("{num_failed} check(s) failed");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
-fn get_day(day_num: i32) -> Box<dyn Problem> {
-    match day_num {
-        1 => Box::new(days::day_01::Day),
-        2 => Box::new(days::day_02::Day),
-        3 => Box::new(days::day_03::Day),
-        4 => Box::new(days::day_04::Day),
-        5 => Box::new(days::day_05::Day),
-        6 => Box::new(days::day_06::Day),
-        7 => Box::new(days::day_07::Day),
-        8 => Box::new(days::day_08::Day),
-        9 => Box::new(days::day_09::Day),
-        10 => Box::new(days::day_10::Day),
-        11 => Box::new(days::day_11::Day),
-        12 => Box::new(days::day_12::Day),
-        13 => Box::new(days::day_13::Day),
-        14 => Box::new(days::day_14::Day),
-        15 => Box::new(days::day_15::Day),
-        16 => Box::new(days::day_16::Day),
-        17 => Box::new(days::day_17::Day),
-        18 => Box::new(days::day_18::Day),
-        19 => Box::new(days::day_19::Day),
-        20 => Box::new(days::day_20::Day),
-        21 => Box::new(days::day_21::Day),
-        22 => Box::new(days::day_22::Day),
-        23 => Box::new(days::day_23::Day),
-        24 => Box::new(days::day_24::Day),
-        _ => panic!("Day {day_num} not implemented")
+fn new_command(args: &Args) -> ExitCode {
+    let day_num = args.day_nums[0];
+    let title = args.title.clone().unwrap_or(format!("Day {day_num}"));
+    match scaffold::new_day(day_num, &title) {
+        Ok(created) => {
+            for path in created {
+                println!("Created {path}");
+            }
+            println!("Registered day {day_num} in src/days.rs");
+            ExitCode::SUCCESS
+        }
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitCode::FAILURE
+        }
     }
 }
 
-fn run(day_num: i32, parts: Vec<i32>) {
-    let day = get_day(day_num);
-    for part in parts {
-        let input = read_input(day_num);
-        match part {
-            1 => day.part_one(&input),
-            2 => day.part_two(&input),
-            _ => panic!("Invalid part")
+fn list_command() -> ExitCode {
+    let title_width = DAYS.iter().map(|day| day.title.len()).max().unwrap_or(0);
+    for day_num in 1..=LAST_DAY {
+        let Ok(day) = get_day(day_num) else {
+            println!("Day {day_num:>2}: not implemented");
+            continue;
         };
+        let path = input_path(day_num);
+        let input_status = if fs::metadata(&path).is_ok() { "" } else { " (no input)" };
+        println!("Day {day_num:>2}: {:title_width$}  {path}{input_status}", day.title);
     }
+    ExitCode::SUCCESS
 }
 
-fn main() {
-    let (day_num, parts) = parse_args();
-    run(day_num, parts);
+fn main() -> ExitCode {
+    let args = match cli::parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {msg}\nRun `aoc2023 help` for usage");
+            return ExitCode::from(2);
+        }
+    };
+    match args.command {
+        Command::Run => run_command(&args),
+        Command::Bench => bench_command(&args),
+        Command::Check => check_command(&args),
+        Command::New => new_command(&args),
+        Command::List => list_command(),
+        Command::Help => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+    }
 }
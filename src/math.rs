@@ -0,0 +1,132 @@
+use std::ops::Rem;
+
+/// Signed integers that [`gcd`] works on.
+pub trait Integer: Copy + PartialEq + Rem<Output = Self> {
+    const ZERO: Self;
+
+    fn wrapping_abs(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+
+                fn wrapping_abs(self) -> Self {
+                    <$t>::wrapping_abs(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i32, i64, i128, isize);
+
+/// Greatest common divisor, never negative except for `gcd(MIN, 0)`, which wraps to `MIN`. `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    if b == T::ZERO {
+        a.wrapping_abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Least common multiple, never negative. Zero if either number is zero.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+/// Greatest common divisor of all the numbers, 0 if there are none.
+pub fn gcd_of(numbers: impl IntoIterator<Item = i64>) -> i64 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// Least common multiple of all the numbers, 1 if there are none.
+pub fn lcm_of(numbers: impl IntoIterator<Item = i64>) -> i64 {
+    numbers.into_iter().fold(1, lcm)
+}
+
+/// Extended Euclid: `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, or `None` if `a` and `m` have a common factor.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Chinese remainder theorem: combines the congruences `x ≡ r (mod m)` into one, `x ≡ r (mod lcm of the m)`,
+/// returned as `(r, m)` with `r` in `0..m`. The moduli don't need to be coprime, but then the congruences can
+/// contradict each other, which gives `None`, as does a combined modulus that does not fit in an `i64`.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    congruences.into_iter().try_fold((0, 1), |(r1, m1), (r2, m2)| {
+        // r1 + m1 * k ≡ r2 (mod m2), solvable if the difference is divisible by the common factor
+        let (g, inverse, _) = extended_gcd(m1, m2);
+        let difference = r2 as i128 - r1 as i128;
+        if difference % g as i128 != 0 {
+            return None;
+        }
+        let m2_g = (m2 / g) as i128;
+        let k = (difference / g as i128).rem_euclid(m2_g) * inverse as i128 % m2_g;
+        let m = i64::try_from(m1 as i128 * m2_g).ok()?;
+        let r = (r1 as i128 + m1 as i128 * k).rem_euclid(m as i128);
+        Some((r as i64, m))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(i128::MAX - 1, 6), 6);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-3, 5), 15);
+        assert_eq!(gcd_of([12, 18, 27]), 3);
+        assert_eq!(lcm_of([2, 3, 4, 5]), 60);
+        assert_eq!(lcm_of([]), 1);
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 6), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Not coprime
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(0, 4), (1, 6)]), None);
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(0, i64::MAX), (1, 2)]), None);
+        assert_eq!(crt([(i64::MAX - 1, i64::MAX), (i64::MIN, 1)]), Some((i64::MAX - 1, i64::MAX)));
+    }
+}
@@ -0,0 +1,339 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::math::gcd;
+
+/// Exact fraction of `i128`s, always in lowest terms with a positive denominator.
+///
+/// The operators panic if a result does not fit, the `checked_` methods return `None` instead. Comparisons never
+/// overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// `num / den` in lowest terms. Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        Rational::checked_new(num, den).expect("rational with a zero denominator or out of range")
+    }
+
+    /// Same as [`Rational::new`], but returns `None` if `den` is zero or the fraction does not fit.
+    pub fn checked_new(num: i128, den: i128) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let divisor = gcd(num, den);
+        let (num, den) = (num / divisor, den / divisor);
+        if den < 0 {
+            Some(Rational {
+                num: num.checked_neg()?,
+                den: den.checked_neg()?,
+            })
+        } else {
+            Some(Rational { num, den })
+        }
+    }
+
+    pub fn numer(self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    /// Largest integer not greater than the fraction.
+    pub fn floor(self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn abs(self) -> Self {
+        if self.num < 0 {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// `1 / self`. Panics if the fraction is zero.
+    pub fn recip(self) -> Self {
+        Rational::new(self.den, self.num)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Rational {
+            num: self.num.checked_neg()?,
+            den: self.den,
+        })
+    }
+
+    pub fn checked_add(self, other: Rational) -> Option<Self> {
+        // Scale by the smallest common denominator to keep the intermediate values small
+        let divisor = gcd(self.den, other.den);
+        let num = self
+            .num
+            .checked_mul(other.den / divisor)?
+            .checked_add(other.num.checked_mul(self.den / divisor)?)?;
+        Rational::checked_new(num, self.den.checked_mul(other.den / divisor)?)
+    }
+
+    pub fn checked_sub(self, other: Rational) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Rational) -> Option<Self> {
+        // Cancel the common factors before multiplying
+        let divisor1 = gcd(self.num, other.den);
+        let divisor2 = gcd(other.num, self.den);
+        let num = (self.num / divisor1).checked_mul(other.num / divisor2)?;
+        let den = (self.den / divisor2).checked_mul(other.den / divisor1)?;
+        Rational::checked_new(num, den)
+    }
+
+    /// Returns `None` also when dividing by zero.
+    pub fn checked_div(self, other: Rational) -> Option<Self> {
+        self.checked_mul(Rational::checked_new(other.den, other.num)?)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Rational { num: n, den: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational::from(n as i128)
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(n: i32) -> Self {
+        Rational::from(n as i128)
+    }
+}
+
+/// Implements an operator and its assigning version with a checked method, panicking on overflow.
+macro_rules! impl_rational_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $checked:ident, $what:literal) => {
+        impl $op for Rational {
+            type Output = Rational;
+
+            fn $fn(self, other: Rational) -> Rational {
+                self.$checked(other).expect(concat!("rational ", $what))
+            }
+        }
+
+        impl $op_assign for Rational {
+            fn $fn_assign(&mut self, other: Rational) {
+                *self = $op::$fn(*self, other);
+            }
+        }
+    };
+}
+
+impl_rational_op!(Add, add, AddAssign, add_assign, checked_add, "addition overflowed");
+impl_rational_op!(Sub, sub, SubAssign, sub_assign, checked_sub, "subtraction overflowed");
+impl_rational_op!(Mul, mul, MulAssign, mul_assign, checked_mul, "multiplication overflowed");
+impl_rational_op!(Div, div, DivAssign, div_assign, checked_div, "division by zero or overflowed");
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        self.checked_neg().expect("rational negation overflowed")
+    }
+}
+
+impl Sum for Rational {
+    fn sum<I: Iterator<Item = Rational>>(iter: I) -> Self {
+        iter.fold(Rational::ZERO, |total, n| total + n)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Compare the integer parts, and then the fractional parts by their reciprocals, like a continued fraction.
+        // Cross-multiplying could overflow.
+        let (mut a_num, mut a_den, mut b_num, mut b_den) = (self.num, self.den, other.num, other.den);
+        let mut reversed = false;
+        loop {
+            let (a_int, b_int) = (a_num.div_euclid(a_den), b_num.div_euclid(b_den));
+            let (a_rem, b_rem) = (a_num.rem_euclid(a_den), b_num.rem_euclid(b_den));
+            let ordering = match (a_int.cmp(&b_int), a_rem, b_rem) {
+                (Ordering::Equal, 0, 0) => Ordering::Equal,
+                (Ordering::Equal, 0, _) => Ordering::Less,
+                (Ordering::Equal, _, 0) => Ordering::Greater,
+                (Ordering::Equal, _, _) => {
+                    // a_rem / a_den < b_rem / b_den exactly when a_den / a_rem > b_den / b_rem
+                    (a_num, a_den, b_num, b_den) = (a_den, a_rem, b_den, b_rem);
+                    reversed = !reversed;
+                    continue;
+                }
+                (ordering, _, _) => ordering,
+            };
+            return if reversed { ordering.reverse() } else { ordering };
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// `num/den`, or just `num` for an integer.
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// Error from parsing a string that is not a fraction of `i128`s with a non-zero denominator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRationalError {
+    pub found: String,
+}
+
+impl Display for ParseRationalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid fraction `{}`", self.found)
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+/// Parses an integer or `num/den`, like the [`Display`] output.
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (num, den) = s.split_once('/').unwrap_or((s, "1"));
+        let rational = match (num.trim().parse(), den.trim().parse()) {
+            (Ok(num), Ok(den)) => Rational::checked_new(num, den),
+            _ => None,
+        };
+        rational.ok_or_else(|| ParseRationalError { found: s.to_string() })
+    }
+}
+
+/// Solves the linear equations given as the rows of an augmented matrix, with the coefficients of the unknowns
+/// followed by the constant term, by Gaussian elimination. Returns the unique solution, or `None` if there is no
+/// solution or more than one, or if the arithmetic overflows. Any number of equations is fine, as long as they
+/// have the same number of terms.
+pub fn solve_linear(mut rows: Vec<Vec<Rational>>) -> Option<Vec<Rational>> {
+    let num_unknowns = rows.first()?.len().checked_sub(1)?;
+    assert!(
+        rows.iter().all(|row| row.len() == num_unknowns + 1),
+        "equations with different numbers of terms"
+    );
+    if rows.len() < num_unknowns {
+        return None;
+    }
+
+    for col in 0..num_unknowns {
+        // Bring a row with a non-zero coefficient to the diagonal
+        let pivot = (col..rows.len()).find(|&row| !rows[row][col].is_zero())?;
+        rows.swap(col, pivot);
+
+        // Eliminate the unknown from all the other rows
+        let pivot_row = rows[col].clone();
+        for (row_index, row) in rows.iter_mut().enumerate() {
+            if row_index == col || row[col].is_zero() {
+                continue;
+            }
+            let factor = row[col].checked_div(pivot_row[col])?;
+            for (term, &pivot_term) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                *term = term.checked_sub(factor.checked_mul(pivot_term)?)?;
+            }
+        }
+    }
+
+    // Any equations left over must have been combinations of the others
+    if rows[num_unknowns..].iter().any(|row| !row[num_unknowns].is_zero()) {
+        return None;
+    }
+    (0..num_unknowns).map(|i| rows[i][num_unknowns].checked_div(rows[i][i])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4);
+        assert_eq!((half.numer(), half.denom()), (1, 2));
+        assert_eq!(Rational::new(3, -6), -half);
+        assert_eq!(half + Rational::new(1, 3), Rational::new(5, 6));
+        assert_eq!(half - Rational::ONE, Rational::new(-1, 2));
+        assert_eq!(half * Rational::from(4), Rational::from(2));
+        assert_eq!(Rational::ONE / Rational::new(-3, 2), Rational::new(-2, 3));
+        assert_eq!(Rational::new(-7, 2).floor(), -4);
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(half.to_integer(), None);
+
+        assert!(Rational::new(1, 3) < half);
+        assert!(Rational::new(-1, 3) > Rational::new(-1, 2));
+        assert!(Rational::new(13, 8) > Rational::new(21, 13));
+        assert!(Rational::new(21, 13) < Rational::new(34, 21));
+        let big = Rational::new(i128::MAX, i128::MAX - 1);
+        assert!(Rational::new(i128::MAX - 1, i128::MAX - 2) > big);
+        assert_eq!(big.checked_mul(big), None);
+        assert_eq!(half.checked_div(Rational::ZERO), None);
+
+        assert_eq!("-3/6".parse(), Ok(Rational::new(-1, 2)));
+        assert_eq!(" 7 ".parse(), Ok(Rational::from(7)));
+        assert_eq!("1/0".parse::<Rational>(), Err(ParseRationalError { found: "1/0".to_string() }));
+        assert_eq!("x/2".parse::<Rational>().unwrap_err().to_string(), "invalid fraction `x/2`");
+        assert_eq!(Rational::new(-10, 4).to_string(), "-5/2");
+        assert_eq!(Rational::from(3).to_string(), "3");
+    }
+
+    #[test]
+    fn test_solve_linear() {
+        let rows = |rows: &[&[i128]]| rows.iter().map(|row| row.iter().map(|&n| Rational::from(n)).collect()).collect();
+
+        // x + y = 3, x - y = 0
+        assert_eq!(solve_linear(rows(&[&[1, 1, 3], &[1, -1, 0]])), Some(vec![Rational::new(3, 2); 2]));
+        // The first unknown is missing from the first equation, and the third equation repeats the second
+        assert_eq!(
+            solve_linear(rows(&[&[0, 2, 4], &[3, 1, 5], &[6, 2, 10]])),
+            Some(vec![Rational::from(1), Rational::from(2)])
+        );
+        assert_eq!(solve_linear(rows(&[&[1, 1, 3], &[2, 2, 6]])), None);
+        assert_eq!(solve_linear(rows(&[&[1, 1, 3], &[1, -1, 0], &[1, 0, 0]])), None);
+        assert_eq!(solve_linear(rows(&[&[i128::MAX, 1, 1], &[1, i128::MAX, 1]])), None);
+    }
+}
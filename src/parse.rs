@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use crate::Error;
+
+/// The non-empty lines of the input, trimmed, keeping track of where each line is in the input.
+pub fn input_lines(input: &str) -> Vec<Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Line::new(i + 1, line))
+        .filter(|line| !line.text.is_empty())
+        .collect()
+}
+
+/// Splits the input into groups of non-empty lines separated by blank lines.
+pub fn input_blocks(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks = vec![];
+    let mut block = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let line = Line::new(i + 1, line);
+        if line.text.is_empty() {
+            if !block.is_empty() {
+                blocks.push(block);
+                block = vec![];
+            }
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// A trimmed input line that remembers its position in the input, so that parse errors can point at the
+/// offending part of it.
+///
+/// The methods taking a `part` expect it to be a slice of `text`, which is what splitting and trimming `text`
+/// gives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+    indent: usize,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, raw_line: &'a str) -> Self {
+        let text = raw_line.trim();
+        let indent = raw_line.len() - raw_line.trim_start().len();
+        Line { number, text, indent }
+    }
+
+    /// 1-based column of `part` in the original line.
+    pub fn column_of(&self, part: &str) -> usize {
+        let offset = (part.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+        if offset <= self.text.len() && self.text.is_char_boundary(offset) {
+            self.indent + self.text[..offset].chars().count() + 1
+        } else {
+            self.indent + 1
+        }
+    }
+
+    pub fn error(&self, part: &str, description: impl Into<String>) -> Error {
+        Error::parse(self.number, self.column_of(part), description)
+    }
+
+    /// Error pointing at the character with the (0-based) index `index` in `text`.
+    pub fn error_at(&self, index: usize, description: impl Into<String>) -> Error {
+        Error::parse(self.number, self.indent + index + 1, description)
+    }
+
+    pub fn parse<T: FromStr>(&self, part: &str, what: &str) -> Result<T, Error> {
+        part.parse().map_err(|_| {
+            if part.is_empty() {
+                self.error(part, format!("expected {what}"))
+            } else {
+                self.error(part, format!("expected {what}, found `{part}`"))
+            }
+        })
+    }
+
+    /// Parses all whitespace separated items in `part`.
+    pub fn parse_words<T: FromStr>(&self, part: &str, what: &str) -> Result<Vec<T>, Error> {
+        part.split_ascii_whitespace().map(|s| self.parse(s, what)).collect()
+    }
+
+    /// Parses all items in `part` separated by `separator`.
+    pub fn parse_separated<T: FromStr>(&self, part: &str, separator: char, what: &str) -> Result<Vec<T>, Error> {
+        part.split(separator).map(|s| self.parse(s.trim(), what)).collect()
+    }
+
+    pub fn split_once(&self, part: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), Error> {
+        part.split_once(delimiter)
+            .ok_or_else(|| self.error(part, format!("expected `{delimiter}` in `{part}`")))
+    }
+
+    /// Parses all the integers in `part`, with an optional sign, skipping everything between them.
+    pub fn ints<T: FromStr>(&self, part: &str) -> Result<Vec<T>, Error> {
+        let bytes = part.as_bytes();
+        let mut ints = Vec::new();
+        let mut index = 0;
+        while index < bytes.len() {
+            let sign_len = usize::from(matches!(bytes[index], b'+' | b'-'));
+            let num_digits = bytes[index + sign_len..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if num_digits == 0 {
+                index += 1;
+                continue;
+            }
+            let end = index + sign_len + num_digits;
+            ints.push(self.parse(&part[index..end], "an integer")?);
+            index = end;
+        }
+        Ok(ints)
+    }
+
+    /// Splits `part` at the first `separator` into a key and a value, both trimmed.
+    pub fn key_value(&self, part: &'a str, separator: &str) -> Result<(&'a str, &'a str), Error> {
+        let (key, value) = self.split_once(part, separator)?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /// Splits `part` into items at `item_separator`, and each item into a key and a value like
+    /// [`Line::key_value`].
+    pub fn key_values(&self, part: &'a str, item_separator: char, separator: &str) -> Result<Vec<(&'a str, &'a str)>, Error> {
+        part.split(item_separator)
+            .map(|item| self.key_value(item.trim(), separator))
+            .collect()
+    }
+
+    /// The inside of `part` between `open` and `close`, ignoring whitespace around them.
+    pub fn delimited(&self, part: &'a str, open: &str, close: &str) -> Result<&'a str, Error> {
+        part.trim()
+            .strip_prefix(open)
+            .and_then(|inner| inner.strip_suffix(close))
+            .ok_or_else(|| self.error(part.trim(), format!("expected `{open}...{close}`, found `{}`", part.trim())))
+    }
+
+    /// Parses `name = (a, b, ...)` into the name and the trimmed items in the parentheses.
+    pub fn name_tuple(&self, part: &'a str) -> Result<(&'a str, Vec<&'a str>), Error> {
+        let (name, tuple) = self.key_value(part, "=")?;
+        let items = self.delimited(tuple, "(", ")")?.split(',').map(str::trim).collect();
+        Ok((name, items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_input_lines() {
+        let input = "
+            Card 1: 41 48 | 83 86
+
+            Card 2: 13 32 | 61 30";
+        let lines = input_lines(input);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].number, 4);
+        assert_eq!(lines[1].text, "Card 2: 13 32 | 61 30");
+
+        let blocks = input_blocks(input);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1][0], lines[1]);
+    }
+
+    #[test]
+    fn test_line_errors() {
+        let line = Line::new(3, "  Card 2: 13 x2 | 61");
+        let (_, numbers) = line.split_once(line.text, ":").unwrap();
+        let (winning, _) = line.split_once(numbers, "|").unwrap();
+        assert_eq!(line.parse_words::<u32>(winning, "a number"), Err(Error::parse(3, 14, "expected a number, found `x2`")));
+        assert_eq!(line.parse_separated::<u32>("1,2,3", ',', "a number").unwrap(), vec![1, 2, 3]);
+        assert_eq!(line.split_once(numbers, ";"), Err(Error::parse(3, 10, "expected `;` in ` 13 x2 | 61`")));
+        assert_eq!(line.error_at(0, "bad"), Error::parse(3, 3, "bad"));
+    }
+
+    #[test]
+    fn test_shapes() {
+        let line = Line::new(1, "19, 13, 30 @ -2,  1, +3");
+        assert_eq!(line.ints::<i64>(line.text), Ok(vec![19, 13, 30, -2, 1, 3]));
+        let line = Line::new(2, "x=1, y=999");
+        assert_eq!(line.ints::<u8>(line.text), Err(Error::parse(2, 8, "expected an integer, found `999`")));
+
+        let line = Line::new(3, "{x=787,m=2655}");
+        let ratings = line.delimited(line.text, "{", "}").unwrap();
+        assert_eq!(line.key_values(ratings, ',', "="), Ok(vec![("x", "787"), ("m", "2655")]));
+        assert_eq!(line.key_value("Game 12: 3 blue", ":"), Ok(("Game 12", "3 blue")));
+        assert_eq!(line.delimited(ratings, "(", ")"), Err(Error::parse(3, 2, "expected `(...)`, found `x=787,m=2655`")));
+
+        let line = Line::new(4, "AAA = (BBB, 11Z)");
+        assert_eq!(line.name_tuple(line.text), Ok(("AAA", vec!["BBB", "11Z"])));
+        let line = Line::new(5, "AAA = BBB");
+        assert_eq!(line.name_tuple(line.text), Err(Error::parse(5, 7, "expected `(...)`, found `BBB`")));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::geom::Point2;
+use crate::math::gcd;
+
+/// Simple polygon with its corners at lattice points, given by the vertices in order around it. The last vertex
+/// connects back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point2>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point2>) -> Self {
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point2] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point2, Point2)> + '_ {
+        self.vertices.iter().copied().zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Twice the area by the shoelace formula, so that it is an integer. Positive if the vertices go clockwise
+    /// with `y` growing downwards, as on the input grids.
+    pub fn twice_signed_area(&self) -> i64 {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+    }
+
+    /// Number of lattice points on the edges.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges().map(|(a, b)| gcd(b.x - a.x, b.y - a.y)).sum()
+    }
+
+    /// Number of lattice points strictly inside, by Pick's theorem: `area = interior + boundary / 2 - 1`.
+    pub fn interior_points(&self) -> i64 {
+        (self.twice_signed_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the edges.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rectangle() {
+        let corners = [(0, 0), (4, 0), (4, 3), (0, 3)].map(|(x, y)| Point2::new(x, y));
+        let rectangle = Polygon::new(corners.to_vec());
+        assert_eq!(rectangle.twice_signed_area(), 24);
+        assert_eq!(rectangle.boundary_points(), 14);
+        assert_eq!(rectangle.interior_points(), 6);
+        assert_eq!(rectangle.lattice_points(), 20);
+
+        let mut reversed = corners.to_vec();
+        reversed.reverse();
+        assert_eq!(Polygon::new(reversed).twice_signed_area(), -24);
+    }
+
+    #[test]
+    fn test_triangle() {
+        // Only the edge to (4, 2) passes through another lattice point, (2, 1)
+        let triangle = Polygon::new(vec![Point2::new(0, 0), Point2::new(4, 2), Point2::new(1, 3)]);
+        assert_eq!(triangle.twice_signed_area(), 10);
+        assert_eq!(triangle.boundary_points(), 4);
+        assert_eq!(triangle.interior_points(), 4);
+    }
+}
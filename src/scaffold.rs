@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::Path;
+
+use aoc2023::fixtures::{fixture_dir, EXPECTED_FILE};
+use aoc2023::input_path;
+
+const TEMPLATE: &str = include_str!("days/day_template.rs");
+const TEMPLATE_TITLE: &str = "pub const TITLE: &str = \"Title\";";
+const TEMPLATE_URL: &str = "//! https://adventofcode.com/2023/day/0\n";
+const DAYS_PATH: &str = "./src/days.rs";
+const EXPECTED_STUB: &str = "[example]\n# part_1 = \n# part_2 = \n";
+
+pub fn day_path(day_num: i32) -> String {
+    format!("./src/days/day_{day_num:02}.rs")
+}
+
+/// Creates the module for a new day from the template and registers it. Also creates an empty input, an empty
+/// example and its expected answers unless they already exist. Returns the created files.
+pub fn new_day(day_num: i32, title: &str) -> Result<Vec<String>, String> {
+    let path = day_path(day_num);
+    if Path::new(&path).exists() {
+        return Err(format!("{path} already exists"));
+    }
+    let days = fs::read_to_string(DAYS_PATH).map_err(|e| format!("Could not read {DAYS_PATH}: {e}"))?;
+    let days = add_registry_entry(&days, day_num)?;
+
+    let mut created = Vec::new();
+    write_new(&path, &day_source(day_num, title))?;
+    created.push(path);
+    fs::write(DAYS_PATH, days).map_err(|e| format!("Could not write {DAYS_PATH}: {e}"))?;
+
+    let input = input_path(day_num);
+    if !Path::new(&input).exists() {
+        write_new(&input, "")?;
+        created.push(input);
+    }
+
+    let fixture_dir = fixture_dir(day_num);
+    fs::create_dir_all(&fixture_dir).map_err(|e| format!("Could not create {}: {e}", fixture_dir.display()))?;
+    for (file, contents) in [("example.txt", ""), (EXPECTED_FILE, EXPECTED_STUB)] {
+        let path = fixture_dir.join(file);
+        if !path.exists() {
+            write_new(&path.to_string_lossy(), contents)?;
+            created.push(path.to_string_lossy().into_owned());
+        }
+    }
+
+    Ok(created)
+}
+
+fn write_new(path: &str, contents: &str) -> Result<(), String> {
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {e}", dir.display()))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Could not write {path}: {e}"))
+}
+
+fn day_source(day_num: i32, title: &str) -> String {
+    let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+    TEMPLATE
+        .replace(TEMPLATE_URL, &format!("//! https://adventofcode.com/2023/day/{day_num}\n"))
+        .replace(TEMPLATE_TITLE, &format!("pub const TITLE: &str = \"{title}\";"))
+}
+
+/// Adds `day_num => day_XX,` to the `days!` list in `days.rs`, keeping the list sorted.
+fn add_registry_entry(days: &str, day_num: i32) -> Result<String, String> {
+    let start = days.find("days! {\n").ok_or("Could not find the `days!` list in days.rs")? + "days! {\n".len();
+    let end = start + days[start..].find("\n}").ok_or("Could not find the end of the `days!` list in days.rs")?;
+
+    let mut entries: Vec<(i32, String)> = Vec::new();
+    for line in days[start..end].lines() {
+        let number = line.split("=>").next().and_then(|n| n.trim().parse().ok());
+        let number = number.ok_or(format!("Unexpected line `{line}` in the `days!` list"))?;
+        if number == day_num {
+            return Err(format!("Day {day_num} is already registered in days.rs"));
+        }
+        entries.push((number, line.to_string()));
+    }
+    entries.push((day_num, format!("    {day_num} => day_{day_num:02},")));
+    entries.sort_by_key(|(number, _)| *number);
+
+    let entries: Vec<String> = entries.into_iter().map(|(_, line)| line).collect();
+    Ok(format!("{}{}{}", &days[..start], entries.join("\n"), &days[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_add_registry_entry() {
+        let days = "use x;\n\ndays! {\n    1 => day_01,\n    3 => day_03,\n}\n\nfn f() {}\n";
+        let days = add_registry_entry(days, 2).unwrap();
+        assert_eq!(days, "use x;\n\ndays! {\n    1 => day_01,\n    2 => day_02,\n    3 => day_03,\n}\n\nfn f() {}\n");
+        let days = add_registry_entry(&days, 25).unwrap();
+        assert!(days.contains("    3 => day_03,\n    25 => day_25,\n}"));
+        assert!(add_registry_entry(&days, 3).is_err());
+        assert!(add_registry_entry("fn f() {}", 3).is_err());
+    }
+
+    #[test]
+    fn test_day_source() {
+        let source = day_source(25, "Snowverload");
+        assert!(source.starts_with("//! https://adventofcode.com/2023/day/25\n"));
+        assert!(source.contains("pub const TITLE: &str = \"Snowverload\";"));
+        assert!(!source.contains(TEMPLATE_URL));
+        assert!(!source.contains(TEMPLATE_TITLE));
+        assert!(day_source(3, "A \"quoted\" title").contains(r#""A \"quoted\" title""#));
+    }
+}
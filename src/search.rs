@@ -0,0 +1,182 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A state space to search: the states and the moves between them.
+pub trait Neighbours {
+    type State: Clone + Eq + Hash;
+
+    /// States reachable from `state` with one move, and the costs of the moves.
+    fn neighbours(&self, state: &Self::State) -> Vec<(Self::State, u64)>;
+}
+
+/// A path found by a search, with the states from the start to the goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub cost: u64,
+    pub states: Vec<S>,
+}
+
+/// States seen by a search, with the move that reached each of them first.
+struct Visited<S> {
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new() -> Self {
+        Visited {
+            states: Vec::new(),
+            parents: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, state: S, parent: Option<usize>) -> usize {
+        self.index.insert(state.clone(), self.states.len());
+        self.states.push(state);
+        self.parents.push(parent);
+        self.states.len() - 1
+    }
+
+    fn path(&self, mut index: usize, cost: u64) -> Path<S> {
+        let mut states = vec![self.states[index].clone()];
+        while let Some(parent) = self.parents[index] {
+            states.push(self.states[parent].clone());
+            index = parent;
+        }
+        states.reverse();
+        Path { cost, states }
+    }
+}
+
+/// Breadth-first search for the path with the fewest moves to a state where `is_goal` holds. The cost of the
+/// path is the number of moves, the costs of the moves are ignored.
+pub fn bfs<N: Neighbours>(space: &N, start: N::State, is_goal: impl Fn(&N::State) -> bool) -> Option<Path<N::State>> {
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::from([(visited.insert(start, None), 0)]);
+    while let Some((index, depth)) = queue.pop_front() {
+        if is_goal(&visited.states[index]) {
+            return Some(visited.path(index, depth));
+        }
+        for (next, _) in space.neighbours(&visited.states[index]) {
+            if !visited.index.contains_key(&next) {
+                queue.push_back((visited.insert(next, Some(index)), depth + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Number of moves to every state reachable from `start`, found with a breadth-first search.
+pub fn bfs_distances<N: Neighbours>(space: &N, start: N::State) -> HashMap<N::State, usize> {
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((state, depth)) = queue.pop_front() {
+        for (next, _) in space.neighbours(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), depth + 1);
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Cheapest path from `start` to a state where `is_goal` holds.
+pub fn dijkstra<N: Neighbours>(space: &N, start: N::State, is_goal: impl Fn(&N::State) -> bool) -> Option<Path<N::State>> {
+    astar(space, start, is_goal, |_| 0)
+}
+
+/// Cheapest path from `start` to a state where `is_goal` holds, exploring the states in the order of their cost
+/// so far plus `heuristic`. The heuristic must not overestimate the remaining cost, or the path found may not
+/// be the cheapest.
+pub fn astar<N: Neighbours>(
+    space: &N,
+    start: N::State,
+    is_goal: impl Fn(&N::State) -> bool,
+    heuristic: impl Fn(&N::State) -> u64,
+) -> Option<Path<N::State>> {
+    let mut visited = Visited::new();
+    let mut costs = vec![0];
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, visited.insert(start, None)))]);
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        if cost > costs[index] {
+            // Already reached more cheaply
+            continue;
+        }
+        if is_goal(&visited.states[index]) {
+            return Some(visited.path(index, cost));
+        }
+        for (next, move_cost) in space.neighbours(&visited.states[index]) {
+            let next_cost = cost + move_cost;
+            let next_index = match visited.index.get(&next) {
+                Some(&next_index) if costs[next_index] <= next_cost => continue,
+                Some(&next_index) => {
+                    costs[next_index] = next_cost;
+                    visited.parents[next_index] = Some(index);
+                    next_index
+                }
+                None => {
+                    costs.push(next_cost);
+                    visited.insert(next, Some(index))
+                }
+            };
+            queue.push(Reverse((next_cost + heuristic(&visited.states[next_index]), next_cost, next_index)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    /// Moving between the open tiles of a maze, at the cost of the digit on the tile moved to.
+    struct Maze(Grid<char>);
+
+    impl Neighbours for Maze {
+        type State = Pos;
+
+        fn neighbours(&self, &pos: &Pos) -> Vec<(Pos, u64)> {
+            self.0
+                .neighbours4(pos)
+                .filter_map(|next| self.0[next].to_digit(10).map(|cost| (next, cost as u64)))
+                .collect()
+        }
+    }
+
+    fn maze() -> Maze {
+        Maze(Grid::parse("1111\n1##9\n1#11\n1111", Some).unwrap())
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let path = bfs(&maze, (0, 0), |&pos| pos == (2, 3)).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.states, [(0, 0), (0, 1), (0, 2), (0, 3), (1, 3), (2, 3)]);
+        assert_eq!(bfs(&maze, (0, 0), |&pos| pos == (1, 1)), None);
+
+        let distances = bfs_distances(&maze, (0, 0));
+        assert_eq!(distances.len(), 13);
+        assert_eq!(distances[&(2, 2)], 6);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let maze = maze();
+        let path = dijkstra(&maze, (0, 0), |&pos| pos == (2, 3)).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.states.len(), 8);
+        assert_eq!(path.states[3], (3, 0));
+
+        let heuristic = |&(row, col): &Pos| (row.abs_diff(2) + col.abs_diff(3)) as u64;
+        let path = astar(&maze, (0, 0), |&pos| pos == (2, 3), heuristic).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(astar(&maze, (0, 0), |&pos| pos == (1, 1), heuristic), None);
+    }
+}
@@ -0,0 +1,22 @@
+use aoc2023::days::DAYS;
+use aoc2023::fixtures::{fixture_dir, load_fixtures};
+
+/// Runs every example in `fixtures/` against its day, see [`aoc2023::fixtures`].
+#[test]
+fn test_fixtures() {
+    let mut failures = Vec::new();
+    for day in DAYS {
+        let fixtures = load_fixtures(&fixture_dir(day.number)).unwrap();
+        for fixture in fixtures {
+            let case = format!("day {} {}", day.number, fixture.name);
+            for (&part, expected) in fixture.expected.iter() {
+                assert!(part == 1 || part == 2, "{case} has an answer for part {part}");
+                let value = day.solve(part, &fixture.input);
+                if value.as_ref() != Ok(expected) {
+                    failures.push(format!("{case} part {part}: expected {expected}, got {value:?}"));
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}